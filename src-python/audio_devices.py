@@ -1,34 +1,79 @@
 import pyaudio
-from config import get_output_device
 from log import server_log
 
-def get_output_device_index():
-    full_device_name = get_output_device()
+# A PortAudio name this short could be the start of too many devices to count as a match
+MIN_TRUNCATED_KEY_LENGTH = 8
 
-    # Get all available devices using pyaudio
-    p = pyaudio.PyAudio()
-    matching_devices = []
-    
+def match_key(name):
+    # Same as audio::match_key on the Rust side
+    words = "".join(c if c.isalnum() else " " for c in name.lower()).split()
+    return " ".join(words)
+
+def host_api_key(name):
+    # "Windows WASAPI" contains "wasapi", "Core Audio" contains "coreaudio"
+    return "".join(c for c in name.lower() if c.isalnum())
+
+def get_default_output_index(p):
+    try:
+        return p.get_default_output_device_info()['index']
+    except IOError:
+        return 0
+
+def list_output_devices(p):
+    devices = []
     for i in range(p.get_device_count()):
         device_info = p.get_device_info_by_index(i)
-        device_name = device_info['name']
-        
-        # Only consider output devices and check if device name is substring of full name
-        if device_info['maxOutputChannels'] > 0 and device_name in full_device_name:
-            matching_devices.append({
-                'index': i,
-                'name': device_name,
-                'length': len(device_name)
-            })
-    
-    p.terminate()
-    
-    if matching_devices:
-        # Find device with shortest name (most likely to work with RealtimeTTS)
-        shortest_device = min(matching_devices, key=lambda d: d['length'])
-        server_log(f"Selected device '{shortest_device['name']}' (shortest match for '{full_device_name}')")
-        return shortest_device['index']
-    else:
-        # No matching devices found, use default
-        server_log(f"No matching devices found for '{full_device_name}', using default device")
-        return 0
\ No newline at end of file
+        if device_info['maxOutputChannels'] <= 0:
+            continue
+        devices.append({
+            'index': i,
+            'name': device_info['name'],
+            'channels': device_info['maxOutputChannels'],
+            'host_api': p.get_host_api_info_by_index(device_info['hostApi'])['name'],
+            'match_key': match_key(device_info['name'])
+        })
+    return devices
+
+def get_output_device_index(descriptor):
+    """Returns the pyaudio index for the device Rust described, and a message when it isn't there"""
+    p = pyaudio.PyAudio()
+
+    try:
+        if descriptor is None:
+            return get_default_output_index(p), None
+
+        devices = list_output_devices(p)
+        wanted_host_api = host_api_key(descriptor.host_api)
+
+        def same_host_api(device):
+            return bool(wanted_host_api) and wanted_host_api in host_api_key(device['host_api'])
+
+        # Strictest first; MME and some others cut names at 31 characters, hence the prefix rule
+        rules = [
+            ("name and host API", lambda d: d['name'] == descriptor.name and same_host_api(d)),
+            ("name", lambda d: d['name'] == descriptor.name),
+            ("match key", lambda d: d['match_key'] == descriptor.match_key),
+            ("truncated name", lambda d: len(d['match_key']) >= MIN_TRUNCATED_KEY_LENGTH
+                and descriptor.match_key.startswith(d['match_key'])),
+        ]
+
+        for rule, matches in rules:
+            found = [d for d in devices if matches(d)]
+            if not found:
+                continue
+
+            # Among equally good matches prefer Rust's host API, then the same channel count
+            best = max(found, key=lambda d: (same_host_api(d), d['channels'] == descriptor.channels))
+            server_log(f"Selected device {best['index']} '{best['name']}' on {best['host_api']} by {rule} for '{descriptor.name}' on {descriptor.host_api}")
+            if best['channels'] != descriptor.channels:
+                server_log(f"Channel count differs: {best['channels']} here, {descriptor.channels} in Rust")
+            return best['index'], None
+
+        default_index = get_default_output_index(p)
+        server_log(f"No device matches '{descriptor.name}' on {descriptor.host_api} (key '{descriptor.match_key}'), using default device {default_index}")
+        for device in devices:
+            server_log(f"  Candidate {device['index']}: '{device['name']}' on {device['host_api']}")
+        return default_index, f"output device {descriptor.name} not found • using default"
+
+    finally:
+        p.terminate()
\ No newline at end of file
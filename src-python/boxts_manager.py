@@ -1,4 +1,14 @@
+from collections import deque
+
 class BoxtsManager:
     def __init__(self):
         self.engine = None
-        self.stream = None
\ No newline at end of file
+        self.stream = None
+        self.voice_settings = None
+        # (pyaudio index, name, volume) for each device the last /start or /outputdevice resolved to
+        self.outputs = []
+        # Plays the stream on several devices; None while there is only one
+        self.mirror = None
+        # Utterances waiting for the current one to finish, so each can be skipped on its own
+        self.pending = deque()
+        self.muted = False
\ No newline at end of file
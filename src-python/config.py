@@ -8,14 +8,6 @@ def get_config_path():
     else:
         return "../boxts.conf.toml"
 
-def get_output_device():
-    try:
-        with open(get_config_path(), "rb") as f:
-            config = tomllib.load(f)
-            return config.get("tts", {}).get("output_device", "Default")
-    except (FileNotFoundError, tomllib.TOMLDecodeError):
-        return "Default"
-
 def get_volume():
     try:
         with open(get_config_path(), "rb") as f:
@@ -1,3 +1,4 @@
+from typing import List, Optional
 from pydantic import BaseModel
 
 class SpeakRequest(BaseModel):
@@ -6,5 +7,40 @@ class SpeakRequest(BaseModel):
 class TrainModelRequest(BaseModel):
     filepath: str
 
+class VoiceSettings(BaseModel):
+    speed: float = 1.0
+    temperature: float = 0.85
+    language: str = "en"
+    repetition_penalty: float = 7.0
+    volume: float = 1.0
+
+class DeviceDescriptor(BaseModel):
+    # Resolved by the Rust side from its cpal device list, see audio.rs
+    host_api: str
+    name: str
+    channels: int
+    match_key: str
+
+class OutputTarget(BaseModel):
+    device: DeviceDescriptor
+    # Scales the global and per-voice volume on this device only
+    volume: float = 1.0
+
+class OutputDevicesRequest(BaseModel):
+    # Every utterance plays on all of these at once
+    outputs: List[OutputTarget]
+
+class OutputVolumeRequest(BaseModel):
+    # The device name as sent in the last OutputTarget
+    name: str
+    volume: float
+
 class VoiceRequest(BaseModel):
-    voice: str
\ No newline at end of file
+    voice: str
+    settings: VoiceSettings = VoiceSettings()
+    # Only sent with /start; empty means the default device
+    output_devices: List[OutputTarget] = []
+
+class MuteRequest(BaseModel):
+    # None toggles
+    muted: Optional[bool] = None
\ No newline at end of file
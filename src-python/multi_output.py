@@ -0,0 +1,135 @@
+import queue
+import threading
+import numpy as np
+import pyaudio
+from log import server_log
+
+# Sample formats the engines produce, for scaling the volume per device
+SAMPLE_TYPES = {
+    pyaudio.paFloat32: np.float32,
+    pyaudio.paInt16: np.int16,
+    pyaudio.paInt32: np.int32,
+}
+
+def scale(chunk, sample_format, volume):
+    dtype = SAMPLE_TYPES.get(sample_format)
+    if dtype is None or volume == 1.0:
+        return chunk
+
+    samples = np.frombuffer(chunk, dtype=dtype) * volume
+    if dtype is np.float32:
+        return np.clip(samples, -1.0, 1.0).astype(dtype).tobytes()
+    limits = np.iinfo(dtype)
+    return np.clip(samples, limits.min, limits.max).astype(dtype).tobytes()
+
+# How long a device waits for the others at the start of an utterance before playing anyway
+START_TIMEOUT_SECONDS = 1.0
+
+class DeviceOutput:
+    """One output device with its own playback thread, so a slow device doesn't hold up the rest"""
+
+    def __init__(self, p, index, name, volume, stream_info):
+        self.index = index
+        self.name = name
+        self.volume = volume
+        self.chunks = queue.Queue()
+        sample_format, channels, rate = stream_info
+        # Raises for devices that can't play this format, e.g. a virtual cable fixed to another rate
+        self.stream = p.open(
+            format=sample_format,
+            channels=channels,
+            rate=rate,
+            output=True,
+            output_device_index=index
+        )
+        self.thread = threading.Thread(target=self.play, daemon=True)
+        self.thread.start()
+
+    def play(self):
+        while True:
+            chunk = self.chunks.get()
+            if chunk is None:
+                break
+            try:
+                if isinstance(chunk, threading.Barrier):
+                    chunk.wait(timeout=START_TIMEOUT_SECONDS)
+                else:
+                    self.stream.write(chunk)
+            except threading.BrokenBarrierError:
+                pass
+            except Exception as e:
+                server_log(f"Error playing on device {self.index} '{self.name}': {str(e)}")
+            finally:
+                self.chunks.task_done()
+
+    def clear(self):
+        while True:
+            try:
+                self.chunks.get_nowait()
+                self.chunks.task_done()
+            except queue.Empty:
+                return
+
+    def close(self):
+        self.clear()
+        self.chunks.put(None)
+        self.thread.join(timeout=1.0)
+        self.stream.stop_stream()
+        self.stream.close()
+
+class MirroredOutput:
+    """Plays every chunk from a muted TextToAudioStream on several devices at once.
+
+    Devices wait for each other at the start of every utterance, so they begin it together.
+    Within an utterance each device plays at its own clock and with its own output latency;
+    neither is compensated, so devices with very different buffering can be a few tens of
+    milliseconds apart.
+    """
+
+    def __init__(self, targets, stream_info):
+        # targets are (pyaudio index, device name, volume)
+        self.sample_format = stream_info[0]
+        self.volume = 1.0
+        self.barrier = None
+        self.p = pyaudio.PyAudio()
+        self.outputs = []
+        for index, name, volume in targets:
+            try:
+                self.outputs.append(DeviceOutput(self.p, index, name, volume, stream_info))
+            except Exception as e:
+                server_log(f"Skipping output device {index} '{name}': {str(e)}")
+
+        if not self.outputs:
+            self.p.terminate()
+            raise RuntimeError("none of the output devices could be opened")
+
+    def start_utterance(self):
+        # Queued ahead of the utterance's first chunk on every device
+        self.barrier = threading.Barrier(len(self.outputs))
+        for output in self.outputs:
+            output.chunks.put(self.barrier)
+
+    def write(self, chunk):
+        for output in self.outputs:
+            output.chunks.put(scale(chunk, self.sample_format, self.volume * output.volume))
+
+    def is_playing(self):
+        return any(output.chunks.unfinished_tasks > 0 for output in self.outputs)
+
+    def set_device_volume(self, name, volume):
+        for output in self.outputs:
+            if output.name == name:
+                output.volume = volume
+
+    def clear(self):
+        for output in self.outputs:
+            output.clear()
+        # Devices already waiting would otherwise sit out the timeout for markers that were just dropped
+        if self.barrier is not None:
+            self.barrier.abort()
+
+    def close(self):
+        self.clear()
+        for output in self.outputs:
+            output.close()
+        self.p.terminate()
@@ -1,8 +1,10 @@
 from fastapi import APIRouter
-from models import SpeakRequest, TrainModelRequest, VoiceRequest
+from models import SpeakRequest, TrainModelRequest, VoiceRequest, VoiceSettings, MuteRequest, OutputDevicesRequest, OutputVolumeRequest
 from services.tts_service import (
     speak_text, start_tts, stop_tts, change_voice, 
-    update_volume, change_output_device, send_ready_signal
+    update_volume, change_output_device, send_ready_signal,
+    update_voice_settings, stop_speaking, skip_speaking, set_muted,
+    update_output_volume
 )
 from services.voice_service import clone_voice_from_file
 from services.device_service import list_audio_devices
@@ -17,17 +19,33 @@ async def root():
 async def speak(request: SpeakRequest):
     return await speak_text(request.text)
 
+@router.post("/stopspeaking")
+async def stopspeaking():
+    return await stop_speaking()
+
+@router.post("/skip")
+async def skip():
+    return await skip_speaking()
+
+@router.post("/mute")
+async def mute(request: MuteRequest):
+    return await set_muted(request.muted)
+
 @router.post("/clonevoice")
 async def clonevoice(request: TrainModelRequest):
     return await clone_voice_from_file(request.filepath)
 
 @router.post("/outputdevice")
-async def outputdevice():
-    return await change_output_device()
+async def outputdevice(request: OutputDevicesRequest):
+    return await change_output_device(request.outputs)
+
+@router.post("/outputvolume")
+async def outputvolume(request: OutputVolumeRequest):
+    return await update_output_volume(request.name, request.volume)
 
 @router.post("/start")
 async def start_tts_endpoint(request: VoiceRequest):
-    return await start_tts(request.voice)
+    return await start_tts(request.voice, request.settings, request.output_devices)
 
 @router.post("/volume")
 async def volume_endpoint():
@@ -43,7 +61,11 @@ async def stop_tts_endpoint():
 
 @router.post("/changevoice")
 async def change_voice_endpoint(request: VoiceRequest):
-    return await change_voice(request.voice)
+    return await change_voice(request.voice, request.settings)
+
+@router.post("/voicesettings")
+async def voice_settings_endpoint(request: VoiceSettings):
+    return await update_voice_settings(request)
 
 @router.post("/ready")
 async def ready():
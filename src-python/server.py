@@ -1,7 +1,9 @@
+import asyncio
 from fastapi import FastAPI, WebSocket
 import uvicorn
 from environment import setup_ffmpeg
 from routes import router
+from services.tts_service import play_pending
 from websocket import websocket_endpoint
 
 # Setup FFmpeg for audio processing
@@ -12,6 +14,11 @@ app = FastAPI(title="Boxts TTS Server", version="0.1.0")
 # Include routes
 app.include_router(router)
 
+# Speaks queued utterances one after another
+@app.on_event("startup")
+async def start_speech_queue():
+    asyncio.create_task(play_pending())
+
 # WebSocket endpoint
 @app.websocket("/ws")
 async def websocket_handler(websocket: WebSocket):
@@ -1,13 +1,107 @@
+import asyncio
+from typing import List, Optional
 from RealtimeTTS import TextToAudioStream, CoquiEngine
 from boxts_manager import BoxtsManager
 from audio_devices import get_output_device_index
 from config import get_volume
 from environment import is_production_environment
 from log import server_log
+from models import OutputTarget, VoiceSettings
+from multi_output import MirroredOutput
 from websocket import signal_ready_ws, signal_notification_ws
 
 boxts_manager = BoxtsManager()
 
+PENDING_POLL_SECONDS = 0.1
+
+def get_effective_volume():
+    if boxts_manager.muted:
+        return 0.0
+
+    # Per-voice volume scales the global volume from the config. A voice above 1.0 can only make
+    # up for a lower global volume, louder than full scale would clip.
+    voice_volume = boxts_manager.voice_settings.volume if boxts_manager.voice_settings else 1.0
+    return min(get_volume() * voice_volume, 1.0)
+
+def apply_volume():
+    volume = get_effective_volume()
+    if boxts_manager.mirror is not None:
+        # The stream itself is muted, the mirror scales each device on top of this
+        boxts_manager.mirror.volume = volume
+    elif boxts_manager.stream is not None:
+        device_volume = boxts_manager.outputs[0][2] if boxts_manager.outputs else 1.0
+        boxts_manager.stream.volume = volume * device_volume
+    return volume
+
+def resolve_outputs(outputs: List[OutputTarget]):
+    """(pyaudio index, name, volume) for each device to play on, and messages for those not found"""
+    if not outputs:
+        index, _ = get_output_device_index(None)
+        return [(index, "Default", 1.0)], []
+
+    resolved = []
+    mismatches = []
+    for target in outputs:
+        index, mismatch = get_output_device_index(target.device)
+        if mismatch:
+            mismatches.append(mismatch)
+        # Missing devices all fall back to the default, which only needs to play once
+        if any(index == existing for existing, _, _ in resolved):
+            continue
+        resolved.append((index, target.device.name, target.volume))
+    return resolved, mismatches
+
+def close_mirror():
+    if boxts_manager.mirror is not None:
+        boxts_manager.mirror.close()
+        boxts_manager.mirror = None
+
+def create_stream():
+    """A stream on the only output device, or a muted one whose audio is mirrored to all of them"""
+    close_mirror()
+    outputs = boxts_manager.outputs
+    if len(outputs) > 1:
+        # Devices that fail to open are logged and left out; the stream is only made once some work
+        boxts_manager.mirror = MirroredOutput(outputs, boxts_manager.engine.get_stream_info())
+        boxts_manager.stream = TextToAudioStream(boxts_manager.engine)
+    else:
+        boxts_manager.stream = TextToAudioStream(
+            boxts_manager.engine,
+            output_device_index=outputs[0][0]
+        )
+    apply_volume()
+
+def play(text: str):
+    boxts_manager.stream.feed(text)
+    if boxts_manager.mirror is None:
+        boxts_manager.stream.play_async()
+    else:
+        boxts_manager.mirror.start_utterance()
+        boxts_manager.stream.play_async(muted=True, on_audio_chunk=boxts_manager.mirror.write)
+
+def is_speaking():
+    # The mirror is still playing buffered audio for a while after synthesis finishes
+    mirror = boxts_manager.mirror
+    return boxts_manager.stream.is_playing() or (mirror is not None and mirror.is_playing())
+
+def stop_playback():
+    boxts_manager.stream.stop()
+    if boxts_manager.mirror is not None:
+        boxts_manager.mirror.clear()
+
+def apply_voice_settings(settings: VoiceSettings):
+    boxts_manager.voice_settings = settings
+
+    if boxts_manager.engine is not None:
+        boxts_manager.engine.language = settings.language
+        boxts_manager.engine.set_voice_parameters(
+            speed=settings.speed,
+            temperature=settings.temperature,
+            repetition_penalty=settings.repetition_penalty
+        )
+
+    apply_volume()
+
 async def speak_text(text: str):
     server_log(f"Speaking text: {text}")
     
@@ -15,9 +109,12 @@ async def speak_text(text: str):
         if boxts_manager.stream is None:
             return {"status": "error", "message": "TTS not started. Use /start command first."}
         
-        # Feed text to the stream
-        boxts_manager.stream.feed(text) 
-        boxts_manager.stream.play_async()
+        # Queue behind whatever is still being spoken
+        if is_speaking() or boxts_manager.pending:
+            boxts_manager.pending.append(text)
+            return {"status": "success", "message": f"Queued: {text}"}
+
+        play(text)
         
         return {"status": "success", "message": f"Speaking: {text}"}
         
@@ -25,8 +122,64 @@ async def speak_text(text: str):
         server_log(f"Error speaking text: {str(e)}")
         return {"status": "error", "message": f"Failed to speak text: {str(e)}"}
 
-async def start_tts(voice: str):
-    server_log(f"Starting TTS with voice: {voice}")
+async def play_pending():
+    """Start the next queued utterance whenever the stream falls silent"""
+    while True:
+        await asyncio.sleep(PENDING_POLL_SECONDS)
+        if boxts_manager.stream is None or not boxts_manager.pending or is_speaking():
+            continue
+
+        try:
+            play(boxts_manager.pending.popleft())
+        except Exception as e:
+            server_log(f"Error speaking queued text: {str(e)}")
+
+async def stop_speaking():
+    server_log("Stopping speech")
+
+    try:
+        if boxts_manager.stream is None:
+            return {"status": "error", "message": "TTS not started. Use /start command first."}
+
+        dropped = len(boxts_manager.pending)
+        boxts_manager.pending.clear()
+        stop_playback()
+
+        server_log(f"Speech stopped, {dropped} queued utterance(s) dropped")
+        return {"status": "success", "message": "Speech stopped"}
+
+    except Exception as e:
+        server_log(f"Error stopping speech: {str(e)}")
+        return {"status": "error", "message": f"Failed to stop speech: {str(e)}"}
+
+async def skip_speaking():
+    server_log("Skipping current utterance")
+
+    try:
+        if boxts_manager.stream is None:
+            return {"status": "error", "message": "TTS not started. Use /start command first."}
+
+        # play_pending picks up the next utterance once the stream has stopped
+        stop_playback()
+
+        server_log(f"Skipped, {len(boxts_manager.pending)} utterance(s) left")
+        return {"status": "success", "message": "Skipped"}
+
+    except Exception as e:
+        server_log(f"Error skipping speech: {str(e)}")
+        return {"status": "error", "message": f"Failed to skip: {str(e)}"}
+
+async def set_muted(muted: Optional[bool]):
+    boxts_manager.muted = not boxts_manager.muted if muted is None else muted
+    server_log(f"Muted: {boxts_manager.muted}")
+
+    apply_volume()
+
+    state = "muted" if boxts_manager.muted else "unmuted"
+    return {"status": "success", "message": state, "muted": boxts_manager.muted}
+
+async def start_tts(voice: str, settings: VoiceSettings, output_devices: Optional[List[OutputTarget]] = None):
+    server_log(f"Starting TTS with voice: {voice} ({settings})")
     
     try:
         # Check if engine already exists
@@ -38,9 +191,9 @@ async def start_tts(voice: str):
 
         await signal_notification_ws("configuring output device")
 
-        output_device_index = get_output_device_index()
-        volume = get_volume()
-        server_log(f"Selected output device index: {output_device_index}")
+        boxts_manager.outputs, device_mismatches = resolve_outputs(output_devices or [])
+        boxts_manager.voice_settings = settings
+        server_log(f"Selected output device indexes: {[index for index, _, _ in boxts_manager.outputs]}")
 
         # Set voices path based on environment
         if is_production_environment():
@@ -58,28 +211,24 @@ async def start_tts(voice: str):
             voices_path=voices_path,
             local_models_path=models_path,
             specific_model="v2.0.3",
-            device="cuda"
+            device="cuda",
+            language=settings.language,
+            speed=settings.speed,
+            temperature=settings.temperature,
+            repetition_penalty=settings.repetition_penalty
         )
 
         await signal_notification_ws("engaging stream")
         
         # Create TextToAudioStream with volume
-        boxts_manager.stream = TextToAudioStream(
-            boxts_manager.engine,
-            output_device_index=output_device_index
-        )
-        
-        boxts_manager.stream.volume = volume
+        create_stream()
 
-        # Feed initial text before starting async streaming (legacy pattern)
-        boxts_manager.stream.feed("TTS SESSION STARTED")
-        
-        # Start async streaming
-        boxts_manager.stream.play_async()
+        # Feed initial text and start async streaming (legacy pattern)
+        play("TTS SESSION STARTED")
         
         server_log(f"TTS started successfully with voice: {voice}")
         await signal_ready_ws()
-        await signal_notification_ws("ready!")
+        await signal_notification_ws(" • ".join(["ready!"] + device_mismatches))
         return {"status": "success", "message": f"TTS started with voice: {voice}"}
         
     except Exception as e:
@@ -92,11 +241,14 @@ async def stop_tts():
     server_log("Stopping TTS and cleaning up resources")
     
     try:
+        boxts_manager.pending.clear()
+
         # Stop and cleanup stream if it exists
         if boxts_manager.stream is not None:
             server_log("Stopping TextToAudioStream...")
             await signal_notification_ws("disengaging stream")
-            boxts_manager.stream.stop()
+            stop_playback()
+            close_mirror()
             
             # Shutdown the engine if it exists
             if boxts_manager.stream.engine is not None:
@@ -124,23 +276,23 @@ async def stop_tts():
         await signal_notification_ws("error stopping tts")
         return {"status": "error", "message": f"Failed to stop TTS: {str(e)}"}
 
-async def change_voice(voice: str):
-    server_log(f"Changing voice to: {voice}")
+async def change_voice(voice: str, settings: VoiceSettings):
+    server_log(f"Changing voice to: {voice} ({settings})")
     
     try:
-        # Check if engine exists
+        # The voice is already saved in the config, /start picks it up from there
         if boxts_manager.engine is None:
             await signal_ready_ws()
             await signal_notification_ws("voice changed • try using /start")
-            return {"status": "error", "message": "TTS engine not started. Use /start command first."}
+            return {"status": "success", "message": "TTS engine not started. Voice will be used on /start."}
         
         # Change the voice on the existing engine
         server_log(f"Setting voice to: {voice}")
         await signal_notification_ws("applying vocal patch")
         boxts_manager.engine.set_voice(voice)
+        apply_voice_settings(settings)
 
-        boxts_manager.stream.feed("NEW VOCAL PATCH APPLIED")
-        boxts_manager.stream.play_async()
+        play("NEW VOCAL PATCH APPLIED")
         
         server_log(f"Voice successfully changed to: {voice}")
         await signal_ready_ws()
@@ -157,12 +309,12 @@ async def update_volume():
     server_log("Updating volume from config")
     
     try:
+        # Nothing to update yet, /start reads the volume from the config
         if boxts_manager.stream is None:
-            return {"status": "error", "message": "TTS not started. Use /start command first."}
+            return {"status": "success", "message": "TTS not started. Volume will be used on /start."}
         
         # Get volume from config and update stream
-        volume = get_volume()
-        boxts_manager.stream.volume = volume
+        volume = apply_volume()
         
         server_log(f"Volume updated to: {volume}")
         return {"status": "success", "message": f"Volume updated to: {volume}"}
@@ -171,33 +323,30 @@ async def update_volume():
         server_log(f"Error updating volume: {str(e)}")
         return {"status": "error", "message": f"Failed to update volume: {str(e)}"}
 
-async def change_output_device():
-    server_log(f"Changing output device")
+async def change_output_device(outputs: List[OutputTarget]):
+    names = ", ".join(f"{target.device.name} ({target.device.host_api})" for target in outputs)
+    server_log(f"Changing output devices to: {names or 'default'}")
     try:
+        # Resolved even without a stream, so a missing device is reported right away
+        boxts_manager.outputs, device_mismatches = resolve_outputs(outputs)
         if boxts_manager.stream is not None:
             await signal_notification_ws("changing output device")
-            # Store current volume before stopping
-            current_volume = boxts_manager.stream.volume
-            boxts_manager.stream.stop()
+            stop_playback()
             
-            output_device_index = get_output_device_index()
-
             # Clear the stream reference
             boxts_manager.stream = None
             
-            # Create new stream with updated output device
-            boxts_manager.stream = TextToAudioStream(
-                boxts_manager.engine,
-                output_device_index=output_device_index
-            )
-            
-            # Restore volume setting
-            boxts_manager.stream.volume = current_volume
+            # Create new stream with updated output devices, volume included
+            create_stream()
         else:
             # No TTS stream exists yet, just log the change
-            server_log("No TTS stream exists yet, device will be used when TTS starts")
+            server_log("No TTS stream exists yet, devices will be used when TTS starts")
         
         await signal_ready_ws()
+        if device_mismatches:
+            message = " • ".join(device_mismatches)
+            await signal_notification_ws(message)
+            return {"status": "error", "message": message}
         await signal_notification_ws("output device changed!")
         return {"status": "success", "message": "Output device changed successfully."}
         
@@ -207,8 +356,42 @@ async def change_output_device():
         await signal_notification_ws("error changing output device")
         return {"status": "error", "message": f"Failed to change output device: {str(e)}"}
 
+async def update_output_volume(name: str, volume: float):
+    server_log(f"Updating volume of {name} to: {volume}")
+
+    try:
+        boxts_manager.outputs = [
+            (index, device, volume if device == name else device_volume)
+            for index, device, device_volume in boxts_manager.outputs
+        ]
+        if boxts_manager.mirror is not None:
+            boxts_manager.mirror.set_device_volume(name, volume)
+        apply_volume()
+
+        return {"status": "success", "message": f"{name} volume updated to: {volume}"}
+
+    except Exception as e:
+        server_log(f"Error updating output device volume: {str(e)}")
+        return {"status": "error", "message": f"Failed to update output device volume: {str(e)}"}
+
 async def send_ready_signal():
     server_log("Manual ready signal requested")
     await signal_ready_ws()
     await signal_notification_ws("ready!")
-    return {"status": "success", "message": "Ready signal sent"}
\ No newline at end of file
+    return {"status": "success", "message": "Ready signal sent"}
+
+async def update_voice_settings(settings: VoiceSettings):
+    server_log(f"Updating voice settings: {settings}")
+
+    try:
+        if boxts_manager.engine is None:
+            return {"status": "success", "message": "TTS not started. Settings will be used on /start."}
+
+        apply_voice_settings(settings)
+
+        server_log("Voice settings updated")
+        return {"status": "success", "message": "Voice settings updated"}
+
+    except Exception as e:
+        server_log(f"Error updating voice settings: {str(e)}")
+        return {"status": "error", "message": f"Failed to update voice settings: {str(e)}"}
\ No newline at end of file
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use tauri::{Emitter, Manager, State};
+use crate::{AppState, config};
+use crate::registry::ArgumentCompletion;
+
+const ALIAS_ACTIONS: &[&str] = &["add", "remove", "list"];
+
+// Splits "/stop; /start bob.json" into its individual steps. "\;" is a ';' inside a step.
+pub fn split_steps(line: &str) -> Vec<String> {
+    split_steps_at(line).into_iter().map(|(_, step)| step).collect()
+}
+
+// Like split_steps, with the byte offset in `line` where each step's piece begins
+pub fn split_steps_at(line: &str) -> Vec<(usize, String)> {
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some((_, ';'))) => {
+                piece.push(';');
+                chars.next();
+            },
+            ';' => {
+                pieces.push((start, std::mem::take(&mut piece)));
+                start = index + 1;
+            },
+            _ => piece.push(c),
+        }
+    }
+    pieces.push((start, piece));
+    pieces.into_iter()
+        .map(|(start, piece)| (start, piece.trim().to_string()))
+        .filter(|(_, step)| !step.is_empty())
+        .collect()
+}
+
+fn expand_step(
+    aliases: &BTreeMap<String, String>,
+    is_builtin: &dyn Fn(&str) -> bool,
+    step: &str,
+    stack: &mut Vec<String>,
+    steps: &mut Vec<String>,
+) -> Result<(), String> {
+    // Plain text is spoken as-is
+    let command_line = match step.strip_prefix('/') {
+        Some(command_line) => command_line,
+        None => {
+            steps.push(step.to_string());
+            return Ok(());
+        }
+    };
+
+    let (name, argument) = match command_line.split_once(' ') {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (command_line, ""),
+    };
+
+    // Built-ins always win, and unknown names are left for dispatch to report
+    let expansion = match aliases.get(name) {
+        Some(expansion) if !is_builtin(name) => expansion,
+        _ => {
+            steps.push(step.to_string());
+            return Ok(());
+        }
+    };
+
+    if stack.iter().any(|seen| seen == name) {
+        stack.push(name.to_string());
+        return Err(format!("Alias loop: {}", stack.join(" -> ")));
+    }
+    stack.push(name.to_string());
+
+    let mut inner = split_steps(expansion);
+    // Anything typed after an alias goes to the last command it expands to
+    if let Some(last) = inner.last_mut().filter(|_| !argument.is_empty()) {
+        last.push(' ');
+        last.push_str(argument);
+    }
+
+    for inner_step in inner {
+        expand_step(aliases, is_builtin, &inner_step, stack, steps)?;
+    }
+
+    stack.pop();
+    Ok(())
+}
+
+// Expands a single input step into the built-in commands and text it stands for
+pub fn expand(aliases: &BTreeMap<String, String>, is_builtin: &dyn Fn(&str) -> bool, step: &str) -> Result<Vec<String>, String> {
+    let mut steps = Vec::new();
+    expand_step(aliases, is_builtin, step, &mut Vec::new(), &mut steps)?;
+    Ok(steps)
+}
+
+pub fn alias_names(state: &State<AppState>) -> Vec<String> {
+    state.config.lock().unwrap().aliases.keys().cloned().collect()
+}
+
+pub fn add_alias(state: &State<AppState>, name: &str, expansion: &str) -> Result<(), String> {
+    let name = name.trim_start_matches('/');
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid alias name: {}", name));
+    }
+    if state.commands.find(name).is_some() {
+        return Err(format!("/{} is a built-in command", name));
+    }
+    if split_steps(expansion).is_empty() {
+        return Err(format!("Alias {} needs at least one command", name));
+    }
+
+    let mut config = state.config.lock().unwrap();
+    let mut aliases = config.aliases.clone();
+    aliases.insert(name.to_string(), expansion.trim().to_string());
+
+    // Refuse definitions that would loop rather than failing every time they're used
+    expand(&aliases, &|command| state.commands.find(command).is_some(), &format!("/{}", name))?;
+
+    config.aliases = aliases;
+    config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))
+}
+
+pub fn remove_alias(state: &State<AppState>, name: &str) -> Result<(), String> {
+    let name = name.trim_start_matches('/');
+    let mut config = state.config.lock().unwrap();
+    if config.aliases.remove(name).is_none() {
+        return Err(format!("No alias named {}", name));
+    }
+    config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))
+}
+
+pub fn list_aliases(state: &State<AppState>) -> String {
+    let config = state.config.lock().unwrap();
+    if config.aliases.is_empty() {
+        return "No aliases defined".to_string();
+    }
+
+    config.aliases
+        .iter()
+        .map(|(name, expansion)| format!("/{} = {}", name, expansion))
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+// Lets the overlay refresh its command list after aliases change
+pub fn emit_commands_changed(app: &tauri::AppHandle) {
+    if let Err(e) = app.emit("commands-changed", ()) {
+        crate::log::tauri_log(&format!("Failed to emit commands-changed event: {}", e));
+    }
+}
+
+// Completes the action, then the alias name for /alias remove
+pub fn complete_argument(app: &tauri::AppHandle, argument: &str) -> ArgumentCompletion {
+    match argument.split_once(' ') {
+        None => ArgumentCompletion {
+            offset: 0,
+            values: ALIAS_ACTIONS.iter().map(|action| action.to_string()).collect(),
+        },
+        Some(("remove", _)) => ArgumentCompletion {
+            offset: "remove ".len(),
+            values: alias_names(&app.state()),
+        },
+        _ => ArgumentCompletion::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILTINS: &[&str] = &["stop", "start", "voice", "volume"];
+
+    fn is_builtin(name: &str) -> bool {
+        BUILTINS.contains(&name)
+    }
+
+    fn aliases(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries.iter().map(|(name, expansion)| (name.to_string(), expansion.to_string())).collect()
+    }
+
+    fn expand_with(entries: &[(&str, &str)], step: &str) -> Result<Vec<String>, String> {
+        expand(&aliases(entries), &is_builtin, step)
+    }
+
+    #[test]
+    fn splits_steps_and_drops_empty_ones() {
+        assert_eq!(split_steps(" /stop ;; /start bob.json ; "), vec!["/stop", "/start bob.json"]);
+    }
+
+    #[test]
+    fn escaped_semicolons_stay_inside_a_step() {
+        assert_eq!(split_steps(r"one\; two; /stop"), vec!["one; two", "/stop"]);
+        assert_eq!(split_steps(r"back\slash"), vec![r"back\slash"]);
+    }
+
+    #[test]
+    fn leaves_text_builtins_and_unknown_commands_alone() {
+        let entries = [("stop", "/volume 0")];
+        assert_eq!(expand_with(&entries, "hello there").unwrap(), vec!["hello there"]);
+        assert_eq!(expand_with(&entries, "/stop").unwrap(), vec!["/stop"]);
+        assert_eq!(expand_with(&entries, "/nothing 3").unwrap(), vec!["/nothing 3"]);
+    }
+
+    #[test]
+    fn expands_nested_aliases_in_order() {
+        let entries = [("quiet", "/volume 0.2; /voice amy"), ("night", "/quiet; good night")];
+        assert_eq!(expand_with(&entries, "/night").unwrap(), vec!["/volume 0.2", "/voice amy", "good night"]);
+    }
+
+    #[test]
+    fn passes_the_argument_to_the_last_command() {
+        let entries = [("restart", "/stop; /start")];
+        assert_eq!(expand_with(&entries, "/restart bob.json").unwrap(), vec!["/stop", "/start bob.json"]);
+    }
+
+    #[test]
+    fn using_an_alias_twice_is_not_a_loop() {
+        let entries = [("quiet", "/volume 0.2"), ("twice", "/quiet; /quiet")];
+        assert_eq!(expand_with(&entries, "/twice").unwrap(), vec!["/volume 0.2", "/volume 0.2"]);
+    }
+
+    #[test]
+    fn detects_direct_and_indirect_loops() {
+        assert_eq!(expand_with(&[("again", "/again")], "/again"), Err("Alias loop: again -> again".to_string()));
+
+        let entries = [("a", "/stop; /b"), ("b", "/c"), ("c", "/a")];
+        assert_eq!(expand_with(&entries, "/a"), Err("Alias loop: a -> b -> c -> a".to_string()));
+    }
+}
@@ -0,0 +1,383 @@
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{Manager, State};
+use crate::{AppState, bridge, config, server_utils};
+use crate::config::OutputDeviceConfig;
+use crate::registry::ArgumentCompletion;
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+// What the backend needs to find the same output device with PortAudio, which lists each
+// device once per host API and sometimes cuts long names short
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DeviceDescriptor {
+    // cpal's host, e.g. WASAPI, ALSA or CoreAudio
+    pub host_api: String,
+    pub name: String,
+    pub channels: u16,
+    // Lowercase words of the name, so spacing and punctuation differences still match
+    pub match_key: String,
+}
+
+pub fn match_key(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn describe(host: &cpal::Host, device: &cpal::Device) -> Option<DeviceDescriptor> {
+    let name = device.name().ok()?;
+    let channels = device
+        .supported_output_configs()
+        .ok()
+        .and_then(|configs| configs.map(|config| config.channels()).max())
+        .or_else(|| device.default_output_config().ok().map(|config| config.channels()))
+        .unwrap_or(2);
+
+    Some(DeviceDescriptor {
+        host_api: host.id().name().to_string(),
+        match_key: match_key(&name),
+        name,
+        channels,
+    })
+}
+
+pub fn output_devices() -> Vec<DeviceDescriptor> {
+    let host = cpal::default_host();
+    match host.output_devices() {
+        Ok(devices) => devices.filter_map(|device| describe(&host, &device)).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub fn default_output_device() -> Option<DeviceDescriptor> {
+    let host = cpal::default_host();
+    host.default_output_device().and_then(|device| describe(&host, &device))
+}
+
+// The connected device saved as `name`, by exact name first and then by match key
+pub fn find_output_device(name: &str) -> Option<DeviceDescriptor> {
+    let devices = output_devices();
+    let key = match_key(name);
+    devices.iter().find(|device| device.name == name)
+        .or_else(|| devices.iter().find(|device| device.match_key == key))
+        .cloned()
+}
+
+// A device to play on and how loud, as sent to the backend
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct OutputTarget {
+    pub device: DeviceDescriptor,
+    pub volume: f32,
+}
+
+// Joins the picks of the multi-select item selector into one /outputdevice argument
+pub const DEVICE_SEPARATOR: &str = " | ";
+
+// Only the spaced separator splits, so a device whose name contains '|' stays whole
+pub fn split_device_names(argument: &str) -> Vec<String> {
+    argument
+        .split(DEVICE_SEPARATOR)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// The connected device a configured name stands for
+pub fn resolve(name: &str) -> Option<DeviceDescriptor> {
+    if name == "Default" {
+        default_output_device()
+    } else {
+        find_output_device(name)
+    }
+}
+
+// The targets for the configured devices, and the configured devices that had to be left out
+pub struct ResolvedOutputs {
+    pub targets: Vec<OutputTarget>,
+    pub missing: Vec<String>,
+}
+
+impl ResolvedOutputs {
+    // The backend only ever sees the devices that are there, so the overlay is told from here
+    pub fn substitution(&self) -> Option<String> {
+        if self.missing.is_empty() {
+            return None;
+        }
+        let playing: Vec<&str> = self.targets.iter().map(|target| target.device.name.as_str()).collect();
+        Some(format!("{} not connected • playing on {}", self.missing.join(", "), playing.join(", ")))
+    }
+}
+
+// What to send the backend for the configured devices. Ones that aren't connected are left out,
+// and if none are, the system default plays instead.
+pub fn targets_for(devices: &[OutputDeviceConfig]) -> Result<ResolvedOutputs, String> {
+    let mut targets: Vec<OutputTarget> = Vec::new();
+    let mut missing = Vec::new();
+    for device in devices {
+        match resolve(&device.name) {
+            // "Default" and the default device by name are the same output
+            Some(descriptor) if targets.iter().any(|target| target.device == descriptor) => {},
+            Some(descriptor) => targets.push(OutputTarget { device: descriptor, volume: device.volume }),
+            None => {
+                crate::log::tauri_log(&format!("Output device {} is not connected, leaving it out", device.name));
+                missing.push(device.name.clone());
+            },
+        }
+    }
+
+    if targets.is_empty() {
+        let device = default_output_device().ok_or_else(|| "No output devices found".to_string())?;
+        targets.push(OutputTarget { device, volume: 1.0 });
+    }
+    Ok(ResolvedOutputs { targets, missing })
+}
+
+pub fn selected_device_names(state: &State<AppState>) -> Vec<String> {
+    config::get_output_devices(state).into_iter().map(|device| device.name).collect()
+}
+
+// The volume comes first, so only the device after it is completed
+pub fn complete_output_volume(app: &tauri::AppHandle, argument: &str) -> ArgumentCompletion {
+    match argument.split_once(' ') {
+        Some((volume, _)) => ArgumentCompletion {
+            offset: volume.len() + 1,
+            values: selected_device_names(&app.state()),
+        },
+        None => ArgumentCompletion::default(),
+    }
+}
+
+async fn switch_output_devices(app: &tauri::AppHandle, targets: Vec<OutputTarget>, message: String) -> Result<(), String> {
+    bridge::send_outputdevice_request(targets).await?;
+    crate::log::tauri_log(&message);
+    // After the request, so it replaces the backend's own "output device changed" notification
+    server_utils::emit_notification(app.clone(), message).await;
+    Ok(())
+}
+
+// e.g. "Headset disconnected • playing on CABLE Input"
+fn change_message(before: &[String], after: &[String], targets: &[OutputTarget]) -> String {
+    let gone: Vec<&str> = before.iter().filter(|name| !after.contains(name)).map(String::as_str).collect();
+    let back: Vec<&str> = after.iter().filter(|name| !before.contains(name)).map(String::as_str).collect();
+
+    let mut parts = Vec::new();
+    if !gone.is_empty() {
+        parts.push(format!("{} disconnected", gone.join(", ")));
+    }
+    if !back.is_empty() {
+        parts.push(format!("{} reconnected", back.join(", ")));
+    }
+    let playing: Vec<&str> = targets.iter().map(|target| target.device.name.as_str()).collect();
+    parts.push(format!("playing on {}", playing.join(", ")));
+    parts.join(" • ")
+}
+
+// Only the names, which cpal can list without opening any device
+fn output_device_names() -> Vec<String> {
+    let host = cpal::default_host();
+    match host.output_devices() {
+        Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// The configured devices found among `available`, matched the same way as find_output_device
+fn connected_names(configured: &[OutputDeviceConfig], available: &[String]) -> Vec<String> {
+    let keys: Vec<String> = available.iter().map(|name| match_key(name)).collect();
+    configured
+        .iter()
+        .filter(|device| match device.name.as_str() {
+            "Default" => !available.is_empty(),
+            name => available.iter().any(|available| available == name) || keys.contains(&match_key(name)),
+        })
+        .map(|device| device.name.clone())
+        .collect()
+}
+
+// What the watcher remembers between checks
+#[derive(Default)]
+struct DeviceWatch {
+    preferred: Vec<OutputDeviceConfig>,
+    connected: Vec<String>,
+}
+
+impl DeviceWatch {
+    // Whether playback has to follow a change. A different device list was already sent by
+    // /outputdevice or /start, so only plugging and unplugging within the same list counts.
+    fn needs_switch(&mut self, preferred: &[OutputDeviceConfig], connected: &[String]) -> bool {
+        if self.preferred != preferred {
+            self.preferred = preferred.to_vec();
+            self.connected = connected.to_vec();
+            return false;
+        }
+        self.connected != connected
+    }
+
+    // Until this is called a failed switch is tried again on the next check
+    fn switched(&mut self, connected: Vec<String>) {
+        self.connected = connected;
+    }
+}
+
+// cpal has no hotplug event either, so check every few seconds which of the configured devices are
+// still there. Playback carries on with the rest while one is gone (or falls back to the default
+// device if none are left) and picks it up again when it returns; the config keeps listing every
+// preferred device throughout. Listing and describing devices blocks, so it runs off the async
+// threads, and descriptors are only built once something changed.
+pub fn watch_output_devices(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut watch = DeviceWatch::default();
+
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            let available = match tauri::async_runtime::spawn_blocking(output_device_names).await {
+                Ok(available) => available,
+                Err(e) => {
+                    crate::log::tauri_log(&format!("Failed to list output devices: {}", e));
+                    continue;
+                },
+            };
+            let preferred = config::get_output_devices(&app.state::<AppState>());
+            let connected = connected_names(&preferred, &available);
+            if !watch.needs_switch(&preferred, &connected) {
+                continue;
+            }
+
+            let before = watch.connected.clone();
+            let result = match tauri::async_runtime::spawn_blocking(move || targets_for(&preferred)).await {
+                Ok(Ok(resolved)) => {
+                    let message = change_message(&before, &connected, &resolved.targets);
+                    switch_output_devices(&app, resolved.targets, message).await
+                },
+                Ok(Err(e)) => Err(e),
+                Err(e) => Err(e.to_string()),
+            };
+
+            match result {
+                Ok(()) => watch.switched(connected),
+                Err(e) => crate::log::tauri_log(&format!("Failed to follow output device change: {}", e)),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configured(names: &[&str]) -> Vec<OutputDeviceConfig> {
+        names
+            .iter()
+            .map(|name| OutputDeviceConfig { name: name.to_string(), volume: 1.0 })
+            .collect()
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn target(name: &str) -> OutputTarget {
+        OutputTarget {
+            device: DeviceDescriptor {
+                host_api: "WASAPI".to_string(),
+                name: name.to_string(),
+                channels: 2,
+                match_key: match_key(name),
+            },
+            volume: 1.0,
+        }
+    }
+
+    #[test]
+    fn match_key_ignores_case_and_punctuation() {
+        assert_eq!(match_key("Speakers (Realtek(R) Audio)"), "speakers realtek r audio");
+        assert_eq!(match_key("  CABLE-Input  "), "cable input");
+    }
+
+    #[test]
+    fn splits_device_names_on_the_spaced_separator_only() {
+        assert_eq!(
+            split_device_names(" Headset |  CABLE Input (VB-Audio Virtual Cable) "),
+            names(&["Headset", "CABLE Input (VB-Audio Virtual Cable)"])
+        );
+        assert_eq!(split_device_names("Line 1|2 (Mixer) | Default"), names(&["Line 1|2 (Mixer)", "Default"]));
+        assert!(split_device_names("  ").is_empty());
+    }
+
+    #[test]
+    fn finds_connected_devices_by_name_or_match_key() {
+        let preferred = configured(&["Headset", "cable input", "Speakers"]);
+        let available = names(&["Headset", "CABLE Input"]);
+        assert_eq!(connected_names(&preferred, &available), names(&["Headset", "cable input"]));
+    }
+
+    #[test]
+    fn default_is_connected_while_any_device_is() {
+        let preferred = configured(&["Default"]);
+        assert_eq!(connected_names(&preferred, &names(&["Speakers"])), names(&["Default"]));
+        assert!(connected_names(&preferred, &[]).is_empty());
+    }
+
+    #[test]
+    fn new_device_list_does_not_switch() {
+        let mut watch = DeviceWatch::default();
+        let preferred = configured(&["Headset", "CABLE Input"]);
+        assert!(!watch.needs_switch(&preferred, &names(&["Headset", "CABLE Input"])));
+        assert!(!watch.needs_switch(&preferred, &names(&["Headset", "CABLE Input"])));
+
+        // Picking other devices while one is unplugged is not a hotplug change either
+        let other = configured(&["Headset"]);
+        assert!(!watch.needs_switch(&other, &[]));
+    }
+
+    #[test]
+    fn unplugging_and_replugging_switch() {
+        let mut watch = DeviceWatch::default();
+        let preferred = configured(&["Headset", "CABLE Input"]);
+        let both = names(&["Headset", "CABLE Input"]);
+        let cable_only = names(&["CABLE Input"]);
+        watch.needs_switch(&preferred, &both);
+
+        assert!(watch.needs_switch(&preferred, &cable_only));
+        watch.switched(cable_only.clone());
+        assert!(!watch.needs_switch(&preferred, &cable_only));
+
+        assert!(watch.needs_switch(&preferred, &both));
+        watch.switched(both.clone());
+        assert!(!watch.needs_switch(&preferred, &both));
+    }
+
+    #[test]
+    fn failed_switch_is_retried() {
+        let mut watch = DeviceWatch::default();
+        let preferred = configured(&["Headset"]);
+        watch.needs_switch(&preferred, &names(&["Headset"]));
+
+        assert!(watch.needs_switch(&preferred, &[]));
+        // switched() was not called, so the next check tries again
+        assert!(watch.needs_switch(&preferred, &[]));
+    }
+
+    #[test]
+    fn describes_changes() {
+        let message = change_message(&names(&["Headset", "CABLE Input"]), &names(&["CABLE Input"]), &[target("CABLE Input")]);
+        assert_eq!(message, "Headset disconnected • playing on CABLE Input");
+
+        let message = change_message(&[], &names(&["Headset"]), &[target("Headset")]);
+        assert_eq!(message, "Headset reconnected • playing on Headset");
+    }
+
+    #[test]
+    fn substitution_names_missing_devices() {
+        let resolved = ResolvedOutputs { targets: vec![target("Speakers")], missing: names(&["Headset"]) };
+        assert_eq!(resolved.substitution().as_deref(), Some("Headset not connected • playing on Speakers"));
+
+        let resolved = ResolvedOutputs { targets: vec![target("Speakers")], missing: Vec::new() };
+        assert_eq!(resolved.substitution(), None);
+    }
+}
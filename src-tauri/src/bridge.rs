@@ -1,5 +1,7 @@
 use reqwest::Client;
 use serde::Serialize;
+use crate::audio::OutputTarget;
+use crate::config::VoiceSettings;
 
 #[derive(Serialize)]
 struct TextPayload {
@@ -14,23 +16,52 @@ struct CloneVoicePayload {
 #[derive(Serialize)]
 struct StartPayload {
     voice: String,
+    settings: VoiceSettings,
+    output_devices: Vec<OutputTarget>,
+}
+
+#[derive(Serialize)]
+struct OutputDevicesPayload {
+    outputs: Vec<OutputTarget>,
+}
+
+#[derive(Serialize)]
+struct OutputVolumePayload {
+    name: String,
+    volume: f32,
 }
 
 const SERVER_BASE_URL: &str = "http://127.0.0.1:8000";
 
+// The backend answers failures with 200 and {"status": "error", "message": ...}, so the body
+// decides whether a request worked
+fn check_status(body: String) -> Result<String, String> {
+    match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(reply) if reply["status"] == "error" => Err(reply["message"]
+            .as_str()
+            .unwrap_or("TTS server reported an error")
+            .to_string()),
+        _ => Ok(body),
+    }
+}
+
+async fn read_reply(sent: Result<reqwest::Response, reqwest::Error>) -> Result<String, String> {
+    match sent {
+        Ok(response) => match response.text().await {
+            Ok(body) => check_status(body),
+            Err(_) => Err("Failed to read response body".to_string()),
+        },
+        Err(e) => Err(format!("Request failed: {}", e)),
+    }
+}
+
 pub async fn send_speak_request(text: String) -> Result<String, String> {
     let client = Client::new();
     let url = format!("{}/speak", SERVER_BASE_URL);
     
     let payload = TextPayload { text };
     
-    match client.post(&url).json(&payload).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    read_reply(client.post(&url).json(&payload).send().await).await
 }
 
 pub async fn send_clonevoice_request(filepath: String) -> Result<String, String> {
@@ -39,109 +70,104 @@ pub async fn send_clonevoice_request(filepath: String) -> Result<String, String>
     
     let payload = CloneVoicePayload { filepath };
     
-    match client.post(&url).json(&payload).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    read_reply(client.post(&url).json(&payload).send().await).await
 }
 
-pub async fn send_start_request(voice: String) -> Result<String, String> {
+pub async fn send_start_request(voice: String, settings: VoiceSettings, output_devices: Vec<OutputTarget>) -> Result<String, String> {
     let client = Client::new();
     let url = format!("{}/start", SERVER_BASE_URL);
     
-    let payload = StartPayload { voice };
+    let payload = StartPayload { voice, settings, output_devices };
     
-    match client.post(&url).json(&payload).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    read_reply(client.post(&url).json(&payload).send().await).await
 }
 
 pub async fn send_volume_request() -> Result<String, String> {
     let client = Client::new();
     let url = format!("{}/volume", SERVER_BASE_URL);
     
-    match client.post(&url).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    read_reply(client.post(&url).send().await).await
 }
 
 pub async fn send_listdevices_request() -> Result<String, String> {
     let client = Client::new();
     let url = format!("{}/listdevices", SERVER_BASE_URL);
     
-    match client.post(&url).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    read_reply(client.post(&url).send().await).await
 }
 
 pub async fn send_stop_request() -> Result<String, String> {
     let client = Client::new();
     let url = format!("{}/stop", SERVER_BASE_URL);
     
-    match client.post(&url).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    read_reply(client.post(&url).send().await).await
 }
 
-pub async fn send_changevoice_request(voice: String) -> Result<String, String> {
+pub async fn send_changevoice_request(voice: String, settings: VoiceSettings) -> Result<String, String> {
     let client = Client::new();
     let url = format!("{}/changevoice", SERVER_BASE_URL);
     
     let request_body = serde_json::json!({
-        "voice": voice
+        "voice": voice,
+        "settings": settings
     });
     
-    match client.post(&url).json(&request_body).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    read_reply(client.post(&url).json(&request_body).send().await).await
 }
 
 pub async fn send_ready_request() -> Result<String, String> {
     let client = Client::new();
     let url = format!("{}/ready", SERVER_BASE_URL);
     
-    match client.post(&url).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    read_reply(client.post(&url).send().await).await
 }
 
-pub async fn send_outputdevice_request() -> Result<String, String> {
+pub async fn send_outputdevice_request(outputs: Vec<OutputTarget>) -> Result<String, String> {
     let client = Client::new();
     let url = format!("{}/outputdevice", SERVER_BASE_URL);
     
-    match client.post(&url).send().await {
-        Ok(response) => match response.text().await {
-            Ok(body) => Ok(body),
-            Err(_) => Err("Failed to read response body".to_string()),
-        },
-        Err(e) => Err(format!("Request failed: {}", e)),
-    }
+    let payload = OutputDevicesPayload { outputs };
+    
+    read_reply(client.post(&url).json(&payload).send().await).await
 }
 
+// `name` is the device name as sent in the last /outputdevice or /start
+pub async fn send_outputvolume_request(name: String, volume: f32) -> Result<String, String> {
+    let client = Client::new();
+    let url = format!("{}/outputvolume", SERVER_BASE_URL);
+    
+    let payload = OutputVolumePayload { name, volume };
+    
+    read_reply(client.post(&url).json(&payload).send().await).await
+}
+
+pub async fn send_voicesettings_request(settings: VoiceSettings) -> Result<String, String> {
+    let client = Client::new();
+    let url = format!("{}/voicesettings", SERVER_BASE_URL);
+    
+    read_reply(client.post(&url).json(&settings).send().await).await
+}
+
+pub async fn send_stopspeaking_request() -> Result<String, String> {
+    let client = Client::new();
+    let url = format!("{}/stopspeaking", SERVER_BASE_URL);
+    
+    read_reply(client.post(&url).send().await).await
+}
+
+pub async fn send_skip_request() -> Result<String, String> {
+    let client = Client::new();
+    let url = format!("{}/skip", SERVER_BASE_URL);
+    
+    read_reply(client.post(&url).send().await).await
+}
+
+// `None` toggles; the reply's "muted" field says where it ended up
+pub async fn send_mute_request(muted: Option<bool>) -> Result<String, String> {
+    let client = Client::new();
+    let url = format!("{}/mute", SERVER_BASE_URL);
+    
+    let request_body = serde_json::json!({ "muted": muted });
+    
+    read_reply(client.post(&url).json(&request_body).send().await).await
+}
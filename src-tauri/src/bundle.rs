@@ -0,0 +1,413 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::config::{self, BoxtsConfig, OutputDeviceConfig};
+use crate::utils;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const CONFIG_NAME: &str = "config.json";
+const VOICES_DIR: &str = "voices";
+const MODELS_DIR: &str = "models";
+const BUNDLE_VERSION: u32 = 1;
+
+pub const EXPORT_OPTIONS: &[&str] = &["standard", "with models"];
+
+#[derive(Serialize, Deserialize)]
+struct BundleManifest {
+    version: u32,
+    app_version: String,
+    created: u64,
+    voices: Vec<VoiceEntry>,
+    includes_models: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VoiceEntry {
+    name: String,
+    size: u64,
+    modified: u64,
+}
+
+pub struct ImportSummary {
+    pub config: BoxtsConfig,
+    pub voices_added: usize,
+    pub voices_renamed: Vec<(String, String)>,
+    pub voices_skipped: usize,
+    pub models_added: usize,
+}
+
+fn unix_time(time: std::time::SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn zip_error(e: zip::result::ZipError) -> String {
+    format!("Archive error: {}", e)
+}
+
+fn io_error(e: std::io::Error) -> String {
+    format!("File error: {}", e)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// Voice embeddings are the JSON files written by voice_cloning.py
+fn validate_voice(name: &str, data: &[u8]) -> Result<(), String> {
+    if name.contains(['/', '\\']) || !name.ends_with(".json") {
+        return Err(format!("Invalid voice file name: {}", name));
+    }
+
+    let value: serde_json::Value = serde_json::from_slice(data)
+        .map_err(|e| format!("Voice {} is not valid JSON: {}", name, e))?;
+    if value.get("gpt_cond_latent").is_none() || value.get("speaker_embedding").is_none() {
+        return Err(format!("Voice {} is missing speaker embeddings", name));
+    }
+
+    Ok(())
+}
+
+// Picks "name-imported.json", then "name-imported-2.json" and so on
+fn free_voice_name(voices_path: &Path, name: &str) -> String {
+    let stem = name.trim_end_matches(".json");
+    let mut candidate = format!("{}-imported.json", stem);
+    let mut counter = 2;
+    while voices_path.join(&candidate).exists() {
+        candidate = format!("{}-imported-{}.json", stem, counter);
+        counter += 1;
+    }
+    candidate
+}
+
+// Devices from another machine rarely exist here, so keep the local choice unless all of them do.
+// With no devices listed at all there is nothing to compare against, so the import wins.
+fn output_devices_to_keep(imported: &[OutputDeviceConfig], current: &[OutputDeviceConfig], available: &[String]) -> Vec<OutputDeviceConfig> {
+    let all_present = imported
+        .iter()
+        .all(|device| device.name == "Default" || available.contains(&device.name));
+    if available.is_empty() || all_present {
+        imported.to_vec()
+    } else {
+        current.to_vec()
+    }
+}
+
+// Points the imported config at renamed voices: the active voice and each voice's settings move
+// to the new name, and the local voice that kept the old name keeps its own settings
+fn follow_renamed_voices(config: &mut BoxtsConfig, current: &BoxtsConfig, renamed: &[(String, String)]) {
+    for (original, new_name) in renamed {
+        if config.tts.voice == *original {
+            config.tts.voice = new_name.clone();
+        }
+
+        let original_key = config::voice_settings_key(original);
+        if let Some(settings) = config.tts.voice_settings.remove(&original_key) {
+            config.tts.voice_settings.insert(config::voice_settings_key(new_name), settings);
+        }
+        if let Some(local) = current.tts.voice_settings.get(&original_key) {
+            config.tts.voice_settings.insert(original_key, local.clone());
+        }
+    }
+}
+
+pub fn export_bundle(path: &Path, config: &BoxtsConfig, include_models: bool) -> Result<usize, String> {
+    let voices_path = utils::get_voices_path();
+    let models_path = utils::get_models_path();
+
+    let voices: Vec<VoiceEntry> = utils::list_voices()
+        .into_iter()
+        .filter_map(|name| {
+            let metadata = fs::metadata(voices_path.join(&name)).ok()?;
+            Some(VoiceEntry {
+                size: metadata.len(),
+                modified: metadata.modified().map(unix_time).unwrap_or_default(),
+                name,
+            })
+        })
+        .collect();
+
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created: unix_time(std::time::SystemTime::now()),
+        voices,
+        includes_models: include_models,
+    };
+
+    let file = File::create(path).map_err(io_error)?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_NAME, options).map_err(zip_error)?;
+    zip.write_all(&manifest_json).map_err(io_error)?;
+
+    let config_json = serde_json::to_vec_pretty(config).map_err(|e| e.to_string())?;
+    zip.start_file(CONFIG_NAME, options).map_err(zip_error)?;
+    zip.write_all(&config_json).map_err(io_error)?;
+
+    for voice in &manifest.voices {
+        let data = fs::read(voices_path.join(&voice.name)).map_err(io_error)?;
+        zip.start_file(format!("{}/{}", VOICES_DIR, voice.name), options).map_err(zip_error)?;
+        zip.write_all(&data).map_err(io_error)?;
+    }
+
+    if include_models && models_path.is_dir() {
+        // Model checkpoints are already compressed and several GB, so store them as-is
+        let model_options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored)
+            .large_file(true);
+
+        let mut files = Vec::new();
+        collect_files(&models_path, &mut files).map_err(io_error)?;
+        for file_path in files {
+            let relative = match file_path.strip_prefix(&models_path) {
+                Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+                Err(_) => continue,
+            };
+            zip.start_file(format!("{}/{}", MODELS_DIR, relative), model_options).map_err(zip_error)?;
+            let mut source = File::open(&file_path).map_err(io_error)?;
+            std::io::copy(&mut source, &mut zip).map_err(io_error)?;
+        }
+    }
+
+    zip.finish().map_err(zip_error)?;
+    Ok(manifest.voices.len())
+}
+
+pub fn import_bundle(path: &Path, current: &BoxtsConfig) -> Result<ImportSummary, String> {
+    let file = File::open(path).map_err(io_error)?;
+    let mut archive = ZipArchive::new(file).map_err(zip_error)?;
+
+    let manifest: BundleManifest = {
+        let mut entry = archive.by_name(MANIFEST_NAME)
+            .map_err(|_| "Not a boxts setup bundle: manifest missing".to_string())?;
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(io_error)?;
+        serde_json::from_slice(&data).map_err(|e| format!("Invalid bundle manifest: {}", e))?
+    };
+
+    if manifest.version > BUNDLE_VERSION {
+        return Err(format!("Bundle version {} is newer than this boxts supports", manifest.version));
+    }
+
+    let mut config: BoxtsConfig = {
+        let mut entry = archive.by_name(CONFIG_NAME)
+            .map_err(|_| "Bundle is missing its config".to_string())?;
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(io_error)?;
+        serde_json::from_slice(&data).map_err(|e| format!("Invalid config in bundle: {}", e))?
+    };
+
+    // Validate every voice before anything is written to disk
+    let mut voices = Vec::new();
+    for voice in &manifest.voices {
+        let mut entry = archive.by_name(&format!("{}/{}", VOICES_DIR, voice.name))
+            .map_err(|_| format!("Bundle is missing voice {}", voice.name))?;
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(io_error)?;
+        validate_voice(&voice.name, &data)?;
+        voices.push((voice.name.clone(), data));
+    }
+
+    let voices_path = utils::get_voices_path();
+    fs::create_dir_all(&voices_path).map_err(io_error)?;
+
+    let mut voices_renamed = Vec::new();
+    let mut voices_added = 0;
+    let mut voices_skipped = 0;
+    for (name, data) in voices {
+        let target = voices_path.join(&name);
+        let target_name = match fs::read(&target) {
+            Ok(existing) if existing == data => {
+                voices_skipped += 1;
+                continue;
+            },
+            Ok(_) => {
+                let renamed = free_voice_name(&voices_path, &name);
+                voices_renamed.push((name.clone(), renamed.clone()));
+                renamed
+            },
+            Err(_) => name.clone(),
+        };
+        fs::write(voices_path.join(&target_name), &data).map_err(io_error)?;
+        voices_added += 1;
+    }
+
+    follow_renamed_voices(&mut config, current, &voices_renamed);
+
+    config.tts.output_devices = output_devices_to_keep(
+        &config.tts.output_devices,
+        &current.tts.output_devices,
+        &utils::list_output_devices(),
+    );
+
+    let mut models_added = 0;
+    if manifest.includes_models {
+        let models_path = utils::get_models_path();
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).map_err(zip_error)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let relative = match entry.enclosed_name().and_then(|name| name.strip_prefix(MODELS_DIR).ok().map(PathBuf::from)) {
+                Some(relative) => relative,
+                None => continue,
+            };
+
+            // Never overwrite a model that is already cached
+            let target = models_path.join(relative);
+            if target.exists() {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(io_error)?;
+            }
+            let mut output = File::create(&target).map_err(io_error)?;
+            std::io::copy(&mut entry, &mut output).map_err(io_error)?;
+            models_added += 1;
+        }
+    }
+
+    Ok(ImportSummary {
+        config,
+        voices_added,
+        voices_renamed,
+        voices_skipped,
+        models_added,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::VoiceSettings;
+
+    fn settings(speed: f32) -> VoiceSettings {
+        VoiceSettings { speed, ..VoiceSettings::default() }
+    }
+
+    fn config_with(voice: &str, voice_settings: &[(&str, f32)]) -> BoxtsConfig {
+        let mut config = BoxtsConfig::default();
+        config.tts.voice = voice.to_string();
+        for (name, speed) in voice_settings {
+            config.tts.voice_settings.insert(name.to_string(), settings(*speed));
+        }
+        config
+    }
+
+    fn voice_json() -> Vec<u8> {
+        br#"{"gpt_cond_latent": [[0.1]], "speaker_embedding": [0.2]}"#.to_vec()
+    }
+
+    fn devices(names: &[&str]) -> Vec<OutputDeviceConfig> {
+        names
+            .iter()
+            .map(|name| OutputDeviceConfig { name: name.to_string(), volume: 1.0 })
+            .collect()
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    // A fresh, empty folder per test, so tests running in parallel don't see each other's files
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("boxts-bundle-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn accepts_voice_embeddings() {
+        assert!(validate_voice("bob.json", &voice_json()).is_ok());
+    }
+
+    #[test]
+    fn rejects_voice_paths_and_other_files() {
+        for name in ["../bob.json", "voices/bob.json", "voices\\bob.json", "bob.txt"] {
+            assert!(validate_voice(name, &voice_json()).is_err(), "{} was accepted", name);
+        }
+    }
+
+    #[test]
+    fn rejects_voices_without_embeddings() {
+        assert!(validate_voice("bob.json", b"not json").is_err());
+        assert!(validate_voice("bob.json", br#"{"gpt_cond_latent": []}"#).is_err());
+        assert!(validate_voice("bob.json", br#"{"speaker_embedding": []}"#).is_err());
+    }
+
+    #[test]
+    fn counts_up_free_voice_names() {
+        let dir = scratch_dir("names");
+        assert_eq!(free_voice_name(&dir, "bob.json"), "bob-imported.json");
+
+        fs::write(dir.join("bob-imported.json"), b"{}").unwrap();
+        assert_eq!(free_voice_name(&dir, "bob.json"), "bob-imported-2.json");
+
+        fs::write(dir.join("bob-imported-2.json"), b"{}").unwrap();
+        assert_eq!(free_voice_name(&dir, "bob.json"), "bob-imported-3.json");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn takes_imported_devices_that_are_all_here() {
+        let imported = devices(&["Default", "Headset"]);
+        let current = devices(&["Speakers"]);
+        let kept = output_devices_to_keep(&imported, &current, &names(&["Headset", "Speakers"]));
+        assert_eq!(kept, imported);
+    }
+
+    #[test]
+    fn keeps_local_devices_when_one_is_missing() {
+        let imported = devices(&["Headset", "Studio Monitors"]);
+        let current = devices(&["Speakers"]);
+        let kept = output_devices_to_keep(&imported, &current, &names(&["Headset", "Speakers"]));
+        assert_eq!(kept, current);
+    }
+
+    #[test]
+    fn takes_imported_devices_when_none_are_listed() {
+        let imported = devices(&["Studio Monitors"]);
+        assert_eq!(output_devices_to_keep(&imported, &devices(&["Speakers"]), &[]), imported);
+    }
+
+    #[test]
+    fn renamed_voices_take_their_settings_along() {
+        let current = config_with("bob.json", &[("bob", 1.0)]);
+        let mut imported = config_with("bob.json", &[("bob", 1.5), ("amy", 0.8)]);
+        let renamed = vec![("bob.json".to_string(), "bob-imported.json".to_string())];
+
+        follow_renamed_voices(&mut imported, &current, &renamed);
+        assert_eq!(imported.tts.voice, "bob-imported.json");
+        assert_eq!(imported.tts.voice_settings["bob-imported"], settings(1.5));
+        assert_eq!(imported.tts.voice_settings["bob"], settings(1.0));
+        assert_eq!(imported.tts.voice_settings["amy"], settings(0.8));
+    }
+
+    #[test]
+    fn renaming_an_inactive_voice_leaves_the_active_one() {
+        let current = config_with("amy.json", &[]);
+        let mut imported = config_with("amy.json", &[("bob", 1.5)]);
+        let renamed = vec![("bob.json".to_string(), "bob-imported-2.json".to_string())];
+
+        follow_renamed_voices(&mut imported, &current, &renamed);
+        assert_eq!(imported.tts.voice, "amy.json");
+        assert_eq!(imported.tts.voice_settings["bob-imported-2"], settings(1.5));
+        assert!(!imported.tts.voice_settings.contains_key("bob"));
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
+use crate::aliases;
+
+// Loading a voice for the first time can take a while on slower machines
+const READY_TIMEOUT: Duration = Duration::from_secs(300);
+pub const RESULT_SEPARATOR: &str = " • ";
+
+tokio::task_local! {
+    // The chain run whose steps execute on the current task
+    static CURRENT_RUN: u64;
+}
+
+// Handed out by begin() and given back to finish(), so concurrent input never mixes runs
+pub struct ChainRun(u64);
+
+// Tracks backend ready signals and gathers notifications per running chain of steps
+pub struct ChainState {
+    ready: watch::Sender<u64>,
+    next_run: AtomicU64,
+    notifications: Mutex<HashMap<u64, Vec<String>>>,
+    // The backend can't say which request a message answers, so its ready signals and
+    // notifications go to the latest chain that is still running
+    backend_owner: Mutex<Option<u64>>,
+}
+
+impl Default for ChainState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChainState {
+    pub fn new() -> Self {
+        Self {
+            ready: watch::channel(0).0,
+            next_run: AtomicU64::new(0),
+            notifications: Mutex::new(HashMap::new()),
+            backend_owner: Mutex::new(None),
+        }
+    }
+
+    pub fn signal_ready(&self) {
+        self.ready.send_modify(|count| *count += 1);
+    }
+
+    pub fn ready_count(&self) -> u64 {
+        *self.ready.borrow()
+    }
+
+    // Waits for a ready signal newer than `after`, which may already have arrived
+    pub async fn wait_for_ready(&self, after: u64) -> Result<(), String> {
+        let mut receiver = self.ready.subscribe();
+        let waited = tokio::time::timeout(READY_TIMEOUT, receiver.wait_for(|count| *count > after))
+            .await
+            .map(|ready| ready.map(|_| ()));
+        match waited {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(_)) => Err("Ready signal is no longer available".to_string()),
+            Err(_) => Err("Timed out waiting for the backend to be ready".to_string()),
+        }
+    }
+
+    pub fn begin(&self) -> ChainRun {
+        let id = self.next_run.fetch_add(1, Ordering::Relaxed);
+        self.notifications.lock().unwrap().insert(id, Vec::new());
+        *self.backend_owner.lock().unwrap() = Some(id);
+        ChainRun(id)
+    }
+
+    // Runs the chain's steps so that what they report is collected for `run`
+    pub async fn scope<F: Future>(&self, run: &ChainRun, steps: F) -> F::Output {
+        CURRENT_RUN.scope(run.0, steps).await
+    }
+
+    // Whether the current task is running a chain step
+    pub fn in_run(&self) -> bool {
+        CURRENT_RUN.try_with(|_| ()).is_ok()
+    }
+
+    // Whether a running chain takes the backend's ready signals and notifications
+    pub fn owns_backend(&self) -> bool {
+        self.backend_owner.lock().unwrap().is_some()
+    }
+
+    fn collect_for(&self, run: Option<u64>, message: &str) -> bool {
+        let mut notifications = self.notifications.lock().unwrap();
+        match run.and_then(|id| notifications.get_mut(&id)) {
+            Some(collected) => {
+                collected.push(message.to_string());
+                true
+            },
+            None => false,
+        }
+    }
+
+    // Returns false outside of a chain step, so the caller shows the notification itself
+    pub fn collect_notification(&self, message: &str) -> bool {
+        self.collect_for(CURRENT_RUN.try_with(|id| *id).ok(), message)
+    }
+
+    pub fn collect_backend_notification(&self, message: &str) -> bool {
+        let owner = *self.backend_owner.lock().unwrap();
+        self.collect_for(owner, message)
+    }
+
+    pub fn finish(&self, run: ChainRun) -> Vec<String> {
+        let mut owner = self.backend_owner.lock().unwrap();
+        if *owner == Some(run.0) {
+            *owner = None;
+        }
+        self.notifications.lock().unwrap().remove(&run.0).unwrap_or_default()
+    }
+}
+
+// Commands whose argument may itself contain ';'
+const TAKES_REST_OF_LINE: &[&str] = &["/alias", "/snippet"];
+
+// Splits "/stop; /start bob.json; hello everyone" into steps. /alias and /snippet keep the
+// rest of the line as typed, since alias definitions and snippet text can use ';' themselves.
+pub fn split_input(line: &str) -> Vec<String> {
+    let pieces = aliases::split_steps_at(line);
+    let takes_rest = |(_, piece): &(usize, String)| TAKES_REST_OF_LINE
+        .iter()
+        .any(|command| piece == command || piece.starts_with(&format!("{} ", command)));
+    match pieces.iter().position(takes_rest) {
+        Some(index) => {
+            let mut steps: Vec<String> = pieces[..index].iter().map(|(_, step)| step.clone()).collect();
+            steps.push(line[pieces[index].0..].trim().to_string());
+            steps
+        },
+        None => pieces.into_iter().map(|(_, step)| step).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with<T>(steps: impl Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(steps)
+    }
+
+    #[test]
+    fn splits_steps_and_drops_empty_ones() {
+        assert_eq!(
+            split_input("/stop;; /start bob.json ;  ; hello everyone;"),
+            vec!["/stop", "/start bob.json", "hello everyone"]
+        );
+        assert!(split_input(" ; ;").is_empty());
+    }
+
+    #[test]
+    fn escaped_semicolons_are_spoken() {
+        assert_eq!(split_input(r"wait\; what; /stop"), vec!["wait; what", "/stop"]);
+    }
+
+    #[test]
+    fn quotes_do_not_protect_semicolons() {
+        assert_eq!(split_input(r#"she said "hi; bye""#), vec![r#"she said "hi"#, r#"bye""#]);
+    }
+
+    #[test]
+    fn alias_and_snippet_keep_the_rest_of_the_line() {
+        assert_eq!(split_input("/alias add x a;b"), vec!["/alias add x a;b"]);
+        assert_eq!(
+            split_input(r"/stop; /alias add quiet /volume 0.2; /voice amy\; x"),
+            vec!["/stop", r"/alias add quiet /volume 0.2; /voice amy\; x"]
+        );
+        assert_eq!(split_input("hello; /snippet add sig Bye; see you"), vec!["hello", "/snippet add sig Bye; see you"]);
+        assert_eq!(split_input("/alias; /stop"), vec!["/alias; /stop"]);
+    }
+
+    #[test]
+    fn similar_commands_do_not_take_the_rest_of_the_line() {
+        assert_eq!(split_input("/aliases; /stop"), vec!["/aliases", "/stop"]);
+        assert_eq!(split_input("say /alias x; /stop"), vec!["say /alias x", "/stop"]);
+    }
+
+    #[test]
+    fn notifications_are_collected_per_run() {
+        let chain = ChainState::new();
+        assert!(!chain.in_run());
+        assert!(!chain.collect_notification("outside"));
+
+        let first = chain.begin();
+        let second = chain.begin();
+        run_with(chain.scope(&first, async {
+            assert!(chain.in_run());
+            assert!(chain.collect_notification("first one"));
+        }));
+        run_with(chain.scope(&second, async {
+            assert!(chain.collect_notification("second one"));
+        }));
+
+        assert_eq!(chain.finish(first), vec!["first one"]);
+        assert_eq!(chain.finish(second), vec!["second one"]);
+        assert!(!chain.in_run());
+    }
+
+    #[test]
+    fn the_latest_run_owns_the_backend_until_it_finishes() {
+        let chain = ChainState::new();
+        assert!(!chain.owns_backend());
+        assert!(!chain.collect_backend_notification("nobody listens"));
+
+        let first = chain.begin();
+        let second = chain.begin();
+        assert!(chain.collect_backend_notification("voice changed"));
+
+        // Finishing an older run leaves the newer one in charge
+        assert!(chain.finish(first).is_empty());
+        assert!(chain.owns_backend());
+        assert_eq!(chain.finish(second), vec!["voice changed"]);
+        assert!(!chain.owns_backend());
+    }
+
+    #[test]
+    fn a_ready_signal_may_arrive_before_waiting() {
+        let chain = ChainState::new();
+        let before = chain.ready_count();
+        chain.signal_ready();
+        assert_eq!(chain.ready_count(), before + 1);
+        assert_eq!(run_with(chain.wait_for_ready(before)), Ok(()));
+    }
+}
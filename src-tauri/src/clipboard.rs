@@ -0,0 +1,240 @@
+use regex::Regex;
+use std::time::{Duration, Instant};
+use tauri::{Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use crate::{AppState, config};
+use crate::config::ClipboardConfig;
+use crate::registry::ArgumentCompletion;
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+const IGNORE_ACTIONS: &[&str] = &["add", "remove", "list"];
+
+fn read_text(app: &tauri::AppHandle) -> String {
+    app.clipboard().read_text().unwrap_or_default()
+}
+
+fn check_length(config: &ClipboardConfig, text: &str) -> Result<(), String> {
+    let length = text.chars().count();
+    if length > config.max_length {
+        return Err(format!("Clipboard text is too long ({} characters, limit is {})", length, config.max_length));
+    }
+    Ok(())
+}
+
+// The first ignore pattern `text` matches; broken patterns are logged and skipped
+fn ignored_by<'a>(config: &'a ClipboardConfig, text: &str) -> Option<&'a str> {
+    config.ignore.iter().map(String::as_str).find(|pattern| match Regex::new(pattern) {
+        Ok(regex) => regex.is_match(text),
+        Err(e) => {
+            crate::log::tauri_log(&format!("Invalid clipboard ignore pattern {}: {}", pattern, e));
+            false
+        }
+    })
+}
+
+// Speaks whatever is on the clipboard now; only the length limit applies to an explicit request
+pub async fn speak_clipboard(app: &tauri::AppHandle, state: &State<'_, AppState>) -> Result<String, String> {
+    let text = read_text(app).trim().to_string();
+    if text.is_empty() {
+        return Err("Clipboard has no text".to_string());
+    }
+    check_length(&config::get_clipboard_config(state), &text)?;
+    crate::handle_text(text, state).await
+}
+
+// Texts spoken by the watcher and when, for the de-duplication window
+struct RecentTexts(Vec<(String, Instant)>);
+
+impl RecentTexts {
+    // False if `text` was already spoken within `window`; otherwise remembers it
+    fn admit(&mut self, text: &str, window: Duration) -> bool {
+        self.0.retain(|(_, spoken_at)| spoken_at.elapsed() < window);
+        if self.0.iter().any(|(recent, _)| recent == text) {
+            return false;
+        }
+        self.0.push((text.to_string(), Instant::now()));
+        true
+    }
+}
+
+// Why newly copied text is not spoken, if it isn't
+fn skip_reason(config: &ClipboardConfig, text: &str, recent: &mut RecentTexts) -> Option<String> {
+    if text.is_empty() {
+        return Some("no text".to_string());
+    }
+    if let Err(e) = check_length(config, text) {
+        return Some(e);
+    }
+    if let Some(pattern) = ignored_by(config, text) {
+        return Some(format!("matches ignore pattern {}", pattern));
+    }
+    if !recent.admit(text, Duration::from_secs(config.dedup_seconds)) {
+        return Some(format!("already spoken in the last {}s", config.dedup_seconds));
+    }
+    None
+}
+
+// There is no clipboard change event, so compare the text every half second. Copies made while
+// watching is off are tracked too, so turning it on doesn't read out what was copied earlier.
+pub fn watch_clipboard(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = read_text(&app);
+        let mut recent = RecentTexts(Vec::new());
+
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            let current = read_text(&app);
+            if current == last {
+                continue;
+            }
+            last = current.clone();
+
+            let state = app.state::<AppState>();
+            let config = config::get_clipboard_config(&state);
+            if !config.watch {
+                continue;
+            }
+
+            let text = current.trim();
+            if let Some(reason) = skip_reason(&config, text, &mut recent) {
+                crate::log::tauri_log(&format!("Not speaking copied text: {}", reason));
+                continue;
+            }
+            if let Err(e) = crate::handle_text(text.to_string(), &state).await {
+                crate::log::tauri_log(&format!("Failed to speak copied text: {}", e));
+            }
+        }
+    });
+}
+
+pub fn add_ignore(state: &State<AppState>, pattern: &str) -> Result<(), String> {
+    Regex::new(pattern).map_err(|e| format!("Invalid pattern {}: {}", pattern, e))?;
+
+    let mut config = state.config.lock().unwrap();
+    if config.clipboard.ignore.iter().any(|existing| existing == pattern) {
+        return Err(format!("Already ignoring {}", pattern));
+    }
+    config.clipboard.ignore.push(pattern.to_string());
+    config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))
+}
+
+pub fn remove_ignore(state: &State<AppState>, pattern: &str) -> Result<(), String> {
+    let mut config = state.config.lock().unwrap();
+    let before = config.clipboard.ignore.len();
+    config.clipboard.ignore.retain(|existing| existing != pattern);
+    if config.clipboard.ignore.len() == before {
+        return Err(format!("Not ignoring {}", pattern));
+    }
+    config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))
+}
+
+pub fn list_ignore(state: &State<AppState>) -> String {
+    let config = state.config.lock().unwrap();
+    if config.clipboard.ignore.is_empty() {
+        return "No ignore patterns".to_string();
+    }
+    config.clipboard.ignore.join(" | ")
+}
+
+// Completes watch and ignore, then their options, then the pattern for /clipboard ignore remove
+pub fn complete_argument(app: &tauri::AppHandle, argument: &str) -> ArgumentCompletion {
+    match argument.split_once(' ') {
+        None => ArgumentCompletion {
+            offset: 0,
+            values: vec!["watch".to_string(), "ignore".to_string()],
+        },
+        Some(("watch", _)) => ArgumentCompletion {
+            offset: "watch ".len(),
+            values: vec!["on".to_string(), "off".to_string()],
+        },
+        Some(("ignore", rest)) => match rest.split_once(' ') {
+            None => ArgumentCompletion {
+                offset: "ignore ".len(),
+                values: IGNORE_ACTIONS.iter().map(|action| action.to_string()).collect(),
+            },
+            Some(("remove", _)) => ArgumentCompletion {
+                offset: "ignore remove ".len(),
+                values: config::get_clipboard_config(&app.state()).ignore,
+            },
+            _ => ArgumentCompletion::default(),
+        },
+        _ => ArgumentCompletion::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b";
+
+    fn config_with(ignore: &[&str]) -> ClipboardConfig {
+        ClipboardConfig {
+            ignore: ignore.iter().map(|pattern| pattern.to_string()).collect(),
+            ..ClipboardConfig::default()
+        }
+    }
+
+    #[test]
+    fn admits_text_once_per_window() {
+        let mut recent = RecentTexts(Vec::new());
+        let window = Duration::from_secs(60);
+        assert!(recent.admit("hello", window));
+        assert!(!recent.admit("hello", window));
+        assert!(recent.admit("world", window));
+    }
+
+    #[test]
+    fn admits_text_again_once_the_window_expired() {
+        let mut recent = RecentTexts(Vec::new());
+        assert!(recent.admit("hello", Duration::ZERO));
+        assert!(recent.admit("hello", Duration::ZERO));
+        // Expired entries are dropped rather than piling up
+        assert_eq!(recent.0.len(), 1);
+    }
+
+    #[test]
+    fn skips_text_over_the_length_limit() {
+        let config = ClipboardConfig { max_length: 5, ..config_with(&[]) };
+        let mut recent = RecentTexts(Vec::new());
+        assert!(skip_reason(&config, "hello", &mut recent).is_none());
+
+        let reason = skip_reason(&config, "hello!", &mut recent).unwrap();
+        assert!(reason.contains("too long"), "{}", reason);
+        // Characters are counted, not bytes
+        assert!(skip_reason(&config, "héllö", &mut RecentTexts(Vec::new())).is_none());
+    }
+
+    #[test]
+    fn skips_empty_and_repeated_text() {
+        let config = config_with(&[]);
+        let mut recent = RecentTexts(Vec::new());
+        assert_eq!(skip_reason(&config, "", &mut recent).as_deref(), Some("no text"));
+        assert!(skip_reason(&config, "hello", &mut recent).is_none());
+        assert!(skip_reason(&config, "hello", &mut recent).is_some());
+    }
+
+    #[test]
+    fn default_patterns_ignore_links_and_secrets() {
+        let config = ClipboardConfig::default();
+        assert!(ignored_by(&config, "https://example.com/page?id=3").is_some());
+        assert!(ignored_by(&config, "  ftp://files.example.com  ").is_some());
+        assert!(ignored_by(&config, HASH).is_some());
+        assert!(ignored_by(&config, "hunter2-Correct-Horse-Battery").is_some());
+    }
+
+    #[test]
+    fn default_patterns_keep_words_and_sentences() {
+        let config = ClipboardConfig::default();
+        for text in ["COVID19", "mp3", "hello", "Read https://example.com later", "Version 2.0 is out"] {
+            assert!(ignored_by(&config, text).is_none(), "{} was ignored", text);
+        }
+    }
+
+    #[test]
+    fn invalid_patterns_are_skipped() {
+        let config = config_with(&["(unclosed", "^secret$"]);
+        assert_eq!(ignored_by(&config, "secret"), Some("^secret$"));
+        assert_eq!(ignored_by(&config, "(unclosed"), None);
+    }
+}
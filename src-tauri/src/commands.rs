@@ -1,9 +1,10 @@
 use tauri::{Manager, State};
 use tauri_plugin_dialog::DialogExt;
-use crate::{AppState, config, utils, server_utils, bridge};
+use crate::{AppState, aliases, audio, bundle, clipboard, config, help, hotkeys, layout, monitors, snippets, undo, utils, server_utils, bridge, settings, theme};
 
 pub async fn exit_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
-    // Stop server before exiting
+    // Make sure pending config changes hit the disk, then stop server before exiting
+    config::flush_config(&state).await;
     server_utils::stop_server(state);
     app.cleanup_before_exit();
     app.exit(0);
@@ -11,47 +12,48 @@ pub async fn exit_command(app: tauri::AppHandle, state: State<'_, AppState>) ->
 }
 
 pub async fn nextmonitor_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
-    // Switch to next monitor and get the new monitor ID
-    let (switch_result, new_monitor_id) = utils::switch_to_next_monitor(app.clone()).await?;
-
-    // Save the new monitor ID to config
-    let _ = config::set_monitor_id(&state, new_monitor_id);
-
-    // Re-apply current position on new monitor
-    let current_position_str = config::get_window_position(&state);
-    utils::apply_window_position(app, state, &current_position_str).await?;
-    
-    Ok(switch_result)
+    // Saves the new monitor and re-applies the current position on it
+    monitors::switch_to_next_monitor(app, state).await
 }
 
 pub async fn center_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
-    let result = utils::apply_window_position(app, state.clone(), "center").await?;
-    let _ = config::set_window_position(&state, "center");
-    Ok(result)
+    let _ = config::set_window_anchor(&state, "center");
+    utils::apply_window_position(app, state, "center").await
 }
 
 pub async fn topleft_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
-    let result = utils::apply_window_position(app, state.clone(), "topleft").await?;
-    let _ = config::set_window_position(&state, "topleft");
-    Ok(result)
+    let _ = config::set_window_anchor(&state, "topleft");
+    utils::apply_window_position(app, state, "topleft").await
 }
 
 pub async fn topright_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
-    let result = utils::apply_window_position(app, state.clone(), "topright").await?;
-    let _ = config::set_window_position(&state, "topright");
-    Ok(result)
+    let _ = config::set_window_anchor(&state, "topright");
+    utils::apply_window_position(app, state, "topright").await
 }
 
 pub async fn bottomleft_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
-    let result = utils::apply_window_position(app, state.clone(), "bottomleft").await?;
-    let _ = config::set_window_position(&state, "bottomleft");
-    Ok(result)
+    let _ = config::set_window_anchor(&state, "bottomleft");
+    utils::apply_window_position(app, state, "bottomleft").await
 }
 
 pub async fn bottomright_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
-    let result = utils::apply_window_position(app, state.clone(), "bottomright").await?;
-    let _ = config::set_window_position(&state, "bottomright");
-    Ok(result)
+    let _ = config::set_window_anchor(&state, "bottomright");
+    utils::apply_window_position(app, state, "bottomright").await
+}
+
+pub async fn position_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let argument = argument.unwrap_or_default();
+    let (offset_x, offset_y) = match argument.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        [offset_x, offset_y] => (offset_x.to_string(), offset_y.to_string()),
+        _ => return Err("Usage: /position <x> <y>, e.g. /position 5% 40px".to_string()),
+    };
+    layout::parse_offset(&offset_x)?;
+    layout::parse_offset(&offset_y)?;
+
+    config::set_window_offset(&state, &offset_x, &offset_y).map_err(|e| e.to_string())?;
+    let anchor = config::get_window_position(&state);
+    utils::apply_window_position(app, state, &anchor).await?;
+    Ok(format!("Window offset set to {} {} from {}", offset_x, offset_y, anchor))
 }
 
 pub async fn resetconfig_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
@@ -60,20 +62,93 @@ pub async fn resetconfig_command(app: tauri::AppHandle, state: State<'_, AppStat
     Ok("Config reset to defaults".to_string())
 }
 
-pub async fn outputdevice_command(argument: Option<String>, state: State<'_, crate::AppState>) -> Result<String, String> {
-    match argument {
-        Some(device_name) => {
-            let _ = config::set_output_device(&state, &device_name);
-            crate::log::tauri_log(&format!("Selected output device: {}", device_name));
-            
-            match bridge::send_outputdevice_request().await {
-                Ok(_response) => {
-                    Ok(format!("Output device set to: {}", device_name))
-                },
-                Err(e) => Err(format!("Failed to update output device: {}", e)),
-            }
+pub async fn undo_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let change = state.undo.lock().unwrap().take_undo();
+    let message = match change {
+        Some(change) => match undo::restore(app.clone(), state, change.before, &change.after).await {
+            Ok(_) => Ok(format!("undid {}", change.description)),
+            Err(e) => Err(format!("Failed to undo {}: {}", change.description, e)),
+        },
+        None => Err("nothing to undo".to_string()),
+    };
+
+    let notification = message.clone().unwrap_or_else(|e| e);
+    crate::log::tauri_log(&notification);
+    server_utils::emit_notification(app, notification).await;
+    message
+}
+
+pub async fn redo_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let change = state.undo.lock().unwrap().take_redo();
+    let message = match change {
+        Some(change) => match undo::restore(app.clone(), state, change.after, &change.before).await {
+            Ok(_) => Ok(format!("redid {}", change.description)),
+            Err(e) => Err(format!("Failed to redo {}: {}", change.description, e)),
+        },
+        None => Err("nothing to redo".to_string()),
+    };
+
+    let notification = message.clone().unwrap_or_else(|e| e);
+    crate::log::tauri_log(&notification);
+    server_utils::emit_notification(app, notification).await;
+    message
+}
+
+pub async fn outputdevice_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, crate::AppState>) -> Result<String, String> {
+    // Several devices come joined by the multi-select item selector, e.g. "Headset | CABLE Input"
+    let device_names = argument.as_deref().map(audio::split_device_names).unwrap_or_default();
+    if device_names.is_empty() {
+        return Err("No output device selected".to_string());
+    }
+
+    let _ = config::set_output_devices(&state, &device_names);
+    config::flush_config(&state).await;
+    crate::log::tauri_log(&format!("Selected output devices: {}", device_names.join(", ")));
+    let resolved = match audio::targets_for(&config::get_output_devices(&state)) {
+        Ok(resolved) => resolved,
+        Err(e) => return fail_before_backend(app, format!("Failed to update output devices: {}", e)).await,
+    };
+    let substitution = resolved.substitution();
+    
+    match bridge::send_outputdevice_request(resolved.targets).await {
+        Ok(_response) => match substitution {
+            Some(message) => {
+                server_utils::emit_notification(app, message.clone()).await;
+                Ok(message)
+            },
+            None => Ok(format!("Output devices set to: {}", device_names.join(", "))),
+        },
+        Err(e) => Err(format!("Failed to update output devices: {}", e)),
+    }
+}
+
+// `/outputvolume <volume> [device]`; the device can be left out while only one is selected
+pub async fn outputvolume_command(argument: Option<String>, state: State<'_, crate::AppState>) -> Result<String, String> {
+    let argument = argument.ok_or("No volume selected")?;
+    let (volume_str, device_name) = match argument.split_once(' ') {
+        Some((volume_str, device_name)) => (volume_str, device_name.trim().to_string()),
+        None => match config::get_output_devices(&state).as_slice() {
+            [device] => (argument.as_str(), device.name.clone()),
+            _ => return Err("Several output devices are selected, name one: /outputvolume <volume> <device>".to_string()),
+        },
+    };
+
+    let volume = volume_str.parse::<f32>().map_err(|_| "Invalid volume value".to_string())?;
+    if !(0.0..=2.0).contains(&volume) {
+        return Err("Output device volume must be between 0 and 2".to_string());
+    }
+
+    config::set_output_device_volume(&state, &device_name, volume)?;
+    config::flush_config(&state).await;
+    crate::log::tauri_log(&format!("Selected volume {} for {}", volume, device_name));
+
+    // A disconnected device gets its new volume when it comes back
+    match audio::resolve(&device_name) {
+        Some(device) => match bridge::send_outputvolume_request(device.name, volume).await {
+            Ok(_response) => Ok(format!("{} volume set to: {}", device_name, volume)),
+            Err(e) => Err(format!("Failed to update output device volume: {}", e)),
         },
-        None => Err("No output device selected".to_string()),
+        None => Ok(format!("{} volume set to: {} (not connected)", device_name, volume)),
     }
 }
 
@@ -83,6 +158,7 @@ pub async fn volume_command(argument: Option<String>, state: State<'_, crate::Ap
             match volume_str.parse::<f32>() {
                 Ok(volume) => {
                     let _ = config::set_volume(&state, volume);
+                    config::flush_config(&state).await;
                     crate::log::tauri_log(&format!("Selected volume: {}", volume));
                     
                     // Send volume update request to Python server
@@ -155,16 +231,37 @@ pub async fn restartserver_command(state: State<'_, crate::AppState>) -> Result<
     }
 }
 
-pub async fn start_command(argument: Option<String>, state: State<'_, crate::AppState>) -> Result<String, String> {
+// For commands that wait for the backend's ready signal but fail before reaching it
+async fn fail_before_backend(app: tauri::AppHandle, message: String) -> Result<String, String> {
+    server_utils::emit_notification(app.clone(), message.clone()).await;
+    server_utils::emit_ready(app).await;
+    Err(message)
+}
+
+pub async fn start_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, crate::AppState>) -> Result<String, String> {
     match argument {
         Some(voice_name) => {
             // Save the selected voice to config
             let _ = config::set_voice(&state, &voice_name);
             crate::log::tauri_log(&format!("Selected voice: {}", voice_name));
+            let voice_settings = config::ensure_voice_settings(&state, &voice_name)
+                .unwrap_or_else(|_| config::get_voice_settings(&state, &voice_name));
+            config::flush_config(&state).await;
             
             // Send start request to Python server
-            match bridge::send_start_request(voice_name.clone()).await {
-                Ok(_response) => Ok(format!("TTS started with voice: {}", voice_name)),
+            let resolved = match audio::targets_for(&config::get_output_devices(&state)) {
+                Ok(resolved) => resolved,
+                Err(e) => return fail_before_backend(app, format!("Failed to start TTS: {}", e)).await,
+            };
+            let substitution = resolved.substitution();
+            match bridge::send_start_request(voice_name.clone(), voice_settings, resolved.targets).await {
+                Ok(_response) => {
+                    // After the backend's own "ready!", so it stays on screen
+                    if let Some(message) = substitution {
+                        server_utils::emit_notification(app, message).await;
+                    }
+                    Ok(format!("TTS started with voice: {}", voice_name))
+                },
                 Err(e) => Err(format!("Failed to start TTS: {}", e)),
             }
         },
@@ -198,6 +295,133 @@ pub async fn stop_command() -> Result<String, String> {
     }
 }
 
+// Failures are shown in the overlay, since these usually run from a hotkey with nothing else to report them
+async fn notify_failure(app: tauri::AppHandle, result: Result<String, String>) -> Result<String, String> {
+    if let Err(e) = &result {
+        server_utils::emit_notification(app, e.clone()).await;
+    }
+    result
+}
+
+pub async fn stopspeaking_command(app: tauri::AppHandle) -> Result<String, String> {
+    // Unlike /stop this keeps the engine loaded, it only silences what is playing or queued
+    let result = match bridge::send_stopspeaking_request().await {
+        Ok(_response) => Ok("Speech stopped".to_string()),
+        Err(e) => Err(format!("Failed to stop speech: {}", e)),
+    };
+    notify_failure(app, result).await
+}
+
+pub async fn skip_command(app: tauri::AppHandle) -> Result<String, String> {
+    let result = match bridge::send_skip_request().await {
+        Ok(_response) => Ok("Skipped".to_string()),
+        Err(e) => Err(format!("Failed to skip: {}", e)),
+    };
+    notify_failure(app, result).await
+}
+
+pub async fn repeat_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let last = state.last_utterance.lock().unwrap().clone();
+    let result = match last {
+        Some(text) => crate::handle_text(text, &state).await,
+        None => Err("Nothing to repeat yet".to_string()),
+    };
+    notify_failure(app, result).await
+}
+
+pub async fn mute_command(argument: Option<String>, app: tauri::AppHandle) -> Result<String, String> {
+    let muted = match argument.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some("on") => Some(true),
+        Some("off") => Some(false),
+        Some(other) => return Err(format!("Invalid option: {}. Use 'on' or 'off'.", other)),
+    };
+
+    let response = bridge::send_mute_request(muted)
+        .await
+        .map_err(|e| format!("Failed to change mute: {}", e))?;
+    let muted = serde_json::from_str::<serde_json::Value>(&response)
+        .ok()
+        .and_then(|body| body.get("muted").and_then(|muted| muted.as_bool()))
+        .ok_or("TTS server sent an invalid mute reply")?;
+
+    let message = if muted { "muted" } else { "unmuted" }.to_string();
+    crate::log::tauri_log(&format!("Muted: {}", muted));
+    server_utils::emit_notification(app, message.clone()).await;
+    Ok(message)
+}
+
+pub async fn nextvoice_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let voices = utils::list_voices();
+    let current = config::get_voice(&state).ok();
+    let next = match current.and_then(|current| voices.iter().position(|voice| *voice == current)) {
+        Some(index) => voices.get((index + 1) % voices.len()),
+        None => voices.first(),
+    };
+
+    match next.cloned() {
+        Some(voice) => changevoice_command(Some(voice), state).await,
+        None => {
+            let message = "No voices found".to_string();
+            server_utils::emit_notification(app, message.clone()).await;
+            Err(message)
+        }
+    }
+}
+
+pub async fn clipboard_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let argument = argument.unwrap_or_default();
+    let (action, rest) = match argument.trim().split_once(' ') {
+        Some((action, rest)) => (action, rest.trim()),
+        None => (argument.trim(), ""),
+    };
+
+    let result = match action {
+        "" => {
+            // Speaking needs no confirmation, only failures are shown
+            let result = clipboard::speak_clipboard(&app, &state).await;
+            if let Err(e) = &result {
+                server_utils::emit_notification(app, e.clone()).await;
+            }
+            return result;
+        },
+        "watch" => {
+            let watch = match rest {
+                "" => Ok(!config::get_clipboard_config(&state).watch),
+                "on" => Ok(true),
+                "off" => Ok(false),
+                other => Err(format!("Invalid option: {}. Use 'on' or 'off'.", other)),
+            };
+            watch.and_then(|watch| {
+                config::set_clipboard_watch(&state, watch)
+                    .map(|_| format!("clipboard watch {}", if watch { "on" } else { "off" }))
+                    .map_err(|e| e.to_string())
+            })
+        },
+        "ignore" => match rest.split_once(' ') {
+            Some(("add", pattern)) => clipboard::add_ignore(&state, pattern.trim())
+                .map(|_| format!("Ignoring copied text matching {}", pattern.trim())),
+            Some(("remove", pattern)) => clipboard::remove_ignore(&state, pattern.trim())
+                .map(|_| format!("No longer ignoring {}", pattern.trim())),
+            None if rest.is_empty() || rest == "list" => Ok(clipboard::list_ignore(&state)),
+            _ => Err("Usage: /clipboard ignore add <pattern> | remove <pattern> | list".to_string()),
+        },
+        _ => Err("Usage: /clipboard [watch [on|off] | ignore add|remove|list]".to_string()),
+    };
+
+    match result {
+        Ok(message) => {
+            crate::log::tauri_log(&message);
+            server_utils::emit_notification(app, message.clone()).await;
+            Ok(message)
+        },
+        Err(e) => {
+            server_utils::emit_notification(app, e.clone()).await;
+            Err(e)
+        }
+    }
+}
+
 pub async fn changevoice_command(argument: Option<String>, state: State<'_, crate::AppState>) -> Result<String, String> {
     match argument {
         Some(voice_name) => {
@@ -205,8 +429,12 @@ pub async fn changevoice_command(argument: Option<String>, state: State<'_, crat
             let _ = config::set_voice(&state, &voice_name);
             crate::log::tauri_log(&format!("Selected voice: {}", voice_name));
             
+            let voice_settings = config::ensure_voice_settings(&state, &voice_name)
+                .unwrap_or_else(|_| config::get_voice_settings(&state, &voice_name));
+            config::flush_config(&state).await;
+            
             // Send changevoice request to Python server
-            match bridge::send_changevoice_request(voice_name.clone()).await {
+            match bridge::send_changevoice_request(voice_name.clone(), voice_settings).await {
                 Ok(_response) => Ok(format!("Voice changed to: {}", voice_name)),
                 Err(e) => Err(format!("Failed to change voice: {}", e)),
             }
@@ -243,9 +471,103 @@ pub async fn lostfocus_command(argument: Option<String>, state: State<'_, crate:
     }
 }
 
-pub async fn help_command(app: tauri::AppHandle) -> Result<String, String> {
+pub async fn theme_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let result = match argument.as_deref().map(str::trim) {
+        Some(preset) if !preset.is_empty() => match theme::set_preset(&state, preset) {
+            Ok(()) => theme::apply_theme_and_position(app.clone(), state.clone())
+                .await
+                .map(|_| format!("Theme set to {}", preset)),
+            Err(e) => Err(e),
+        },
+        _ => Err("No theme selected".to_string()),
+    };
+
+    match result {
+        Ok(message) => {
+            crate::log::tauri_log(&message);
+            server_utils::emit_notification(app, message.clone()).await;
+            Ok(message)
+        },
+        Err(e) => {
+            server_utils::emit_notification(app, e.clone()).await;
+            Err(e)
+        }
+    }
+}
+
+pub async fn expand_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let expanded = match argument.as_deref().map(str::trim) {
+        None | Some("") => !*state.expanded.lock().unwrap(),
+        Some("on") => true,
+        Some("off") => false,
+        Some(other) => return Err(format!("Invalid option: {}. Use 'on' or 'off'.", other)),
+    };
+
+    theme::set_expanded(app, state, expanded).await?;
+    Ok(format!("expanded input {}", if expanded { "on" } else { "off" }))
+}
+
+pub async fn followcursor_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let follow_cursor = match argument.as_deref().map(str::trim) {
+        None | Some("") => !config::get_follow_cursor(&state),
+        Some("on") => true,
+        Some("off") => false,
+        Some(other) => return Err(format!("Invalid option: {}. Use 'on' or 'off'.", other)),
+    };
+
+    config::set_follow_cursor(&state, follow_cursor).map_err(|e| e.to_string())?;
+    if !follow_cursor {
+        // Go back to the saved monitor right away rather than on the next show
+        monitors::apply_saved_monitor(app.clone(), state.clone()).await?;
+    }
+
+    let message = format!("follow cursor {}", if follow_cursor { "on" } else { "off" });
+    crate::log::tauri_log(&format!("Follow cursor set to: {}", follow_cursor));
+    server_utils::emit_notification(app, message.clone()).await;
+    Ok(message)
+}
+
+pub async fn hotkey_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let argument = argument.unwrap_or_default();
+    let result = match argument.trim().split_once(' ') {
+        None if argument.trim().is_empty() || argument.trim() == "list" => Ok(hotkeys::list_hotkeys(&state)),
+        Some((action, shortcut)) => hotkeys::bind(&app, &state, action, shortcut),
+        None => Err("Usage: /hotkey <action> <shortcut|none> | list".to_string()),
+    };
+
+    match result {
+        Ok(message) => {
+            crate::log::tauri_log(&message);
+            server_utils::emit_notification(app, message.clone()).await;
+            Ok(message)
+        },
+        Err(e) => {
+            server_utils::emit_notification(app, e.clone()).await;
+            Err(e)
+        }
+    }
+}
+
+pub async fn help_command(argument: Option<String>, app: tauri::AppHandle) -> Result<String, String> {
     use tauri::{WebviewWindowBuilder, WebviewUrl};
-    
+
+    // /help <command> answers inline in the overlay instead of opening the window
+    let name = argument.as_deref().map(|arg| arg.trim().trim_start_matches('/')).unwrap_or_default();
+    if !name.is_empty() {
+        let message = {
+            let state = app.state::<AppState>();
+            let alias = state.config.lock().unwrap().aliases.get(name).cloned();
+            match (state.commands.find(name), alias) {
+                (Some(command), _) => Ok(help::describe_command(&app, command)),
+                (None, Some(expansion)) => Ok(format!("/{} - alias for {}", name, expansion)),
+                (None, None) => Err(format!("Unknown command: {}", name)),
+            }
+        };
+        let notification = message.clone().unwrap_or_else(|e| e);
+        server_utils::emit_notification(app, notification).await;
+        return message;
+    }
+
     crate::log::tauri_log("Opening help window...");
     
     let help_window = WebviewWindowBuilder::new(
@@ -271,3 +593,240 @@ pub async fn help_command(app: tauri::AppHandle) -> Result<String, String> {
     }
 }
 
+pub async fn get_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    match argument {
+        Some(key) => {
+            let key = key.trim();
+            let value = settings::get_value(&state, key)?;
+            let message = format!("{} = {}", key, value);
+            server_utils::emit_notification(app, message.clone()).await;
+            Ok(message)
+        },
+        None => Err("No setting selected".to_string()),
+    }
+}
+
+pub async fn set_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let (key, raw_value) = match argument.as_deref().map(str::trim).and_then(|arg| arg.split_once(' ')) {
+        Some((key, raw_value)) => (key.to_string(), raw_value.trim().to_string()),
+        None => return Err("Usage: /set <key> <value>".to_string()),
+    };
+
+    let previous = settings::get_value(&state, &key).unwrap_or_default();
+    let value = match settings::set_value(&app, &state, &key, &raw_value) {
+        Ok(value) => value,
+        Err(e) => {
+            server_utils::emit_notification(app, e.clone()).await;
+            return Err(e);
+        }
+    };
+    crate::log::tauri_log(&format!("Setting {} set to: {}", key, value));
+
+    // Push the new value to the window or backend straight away
+    settings::apply_setting(app.clone(), state, &key, &previous).await?;
+
+    let message = format!("{} = {}", key, value);
+    server_utils::emit_notification(app, message.clone()).await;
+    Ok(message)
+}
+
+pub async fn alias_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let argument = argument.unwrap_or_default();
+    let (action, rest) = match argument.trim().split_once(' ') {
+        Some((action, rest)) => (action, rest.trim()),
+        None => (argument.trim(), ""),
+    };
+
+    let result = match action {
+        "add" => match rest.split_once(' ') {
+            Some((name, expansion)) => aliases::add_alias(&state, name, expansion)
+                .map(|_| format!("Alias /{} added", name.trim_start_matches('/'))),
+            None => Err("Usage: /alias add <name> <commands>".to_string()),
+        },
+        "remove" => aliases::remove_alias(&state, rest)
+            .map(|_| format!("Alias /{} removed", rest.trim_start_matches('/'))),
+        "list" => Ok(aliases::list_aliases(&state)),
+        _ => Err("Usage: /alias add <name> <commands> | remove <name> | list".to_string()),
+    };
+
+    match result {
+        Ok(message) => {
+            crate::log::tauri_log(&message);
+            if action != "list" {
+                aliases::emit_commands_changed(&app);
+            }
+            server_utils::emit_notification(app, message.clone()).await;
+            Ok(message)
+        },
+        Err(e) => {
+            server_utils::emit_notification(app, e.clone()).await;
+            Err(e)
+        }
+    }
+}
+
+pub async fn say_command(argument: Option<String>, app: tauri::AppHandle) -> Result<String, String> {
+    let name = argument.unwrap_or_default();
+    let result = match snippets::expand_snippet(&app, name.trim()) {
+        Ok(text) => crate::handle_text(text, &app.state()).await,
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = &result {
+        server_utils::emit_notification(app, e.clone()).await;
+    }
+    result
+}
+
+pub async fn snippet_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let argument = argument.unwrap_or_default();
+    let (action, rest) = match argument.trim().split_once(' ') {
+        Some((action, rest)) => (action, rest.trim()),
+        None => (argument.trim(), ""),
+    };
+
+    let result = match action {
+        "add" => match rest.split_once(' ') {
+            Some((name, text)) => snippets::add_snippet(&state, name, text)
+                .map(|_| format!("Snippet {} added", name)),
+            None => Err("Usage: /snippet add <name> <text>".to_string()),
+        },
+        "remove" => snippets::remove_snippet(&state, rest)
+            .map(|_| format!("Snippet {} removed", rest)),
+        "list" => Ok(snippets::list_snippets(&state)),
+        _ => Err("Usage: /snippet add <name> <text> | remove <name> | list".to_string()),
+    };
+
+    match result {
+        Ok(message) => {
+            crate::log::tauri_log(&message);
+            server_utils::emit_notification(app, message.clone()).await;
+            Ok(message)
+        },
+        Err(e) => {
+            server_utils::emit_notification(app, e.clone()).await;
+            Err(e)
+        }
+    }
+}
+
+pub async fn clearhistory_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    state.history.lock().unwrap().clear();
+    crate::log::tauri_log("Input history cleared");
+    server_utils::emit_notification(app, "history cleared".to_string()).await;
+    Ok("History cleared".to_string())
+}
+
+pub async fn export_command(argument: Option<String>, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let include_models = argument.as_deref() == Some("with models");
+
+    {
+        let mut dialog_active = state.dialog_active.lock().unwrap();
+        *dialog_active = true;
+    }
+    
+    let file_path = app
+        .dialog()
+        .file()
+        .add_filter("Boxts Setup", &["zip"])
+        .set_file_name("boxts-setup.zip")
+        .blocking_save_file();
+    
+    {
+        let mut dialog_active = state.dialog_active.lock().unwrap();
+        *dialog_active = false;
+    }
+    
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+
+    let path = match file_path {
+        Some(path) => path.into_path().map_err(|e| format!("Invalid export path: {}", e))?,
+        None => return Ok("Export cancelled".to_string()),
+    };
+
+    crate::log::tauri_log(&format!("Exporting setup bundle to: {:?}", path));
+    server_utils::emit_notification(app.clone(), "exporting setup".to_string()).await;
+
+    let config = state.config.lock().unwrap().clone();
+    let export_path = path.clone();
+    // Model checkpoints can be several GB, so keep the copy off the async runtime
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        bundle::export_bundle(&export_path, &config, include_models)
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?;
+
+    match result {
+        Ok(voice_count) => {
+            server_utils::emit_notification(app, format!("setup exported • {} voices", voice_count)).await;
+            Ok(format!("Setup exported to: {:?}", path))
+        },
+        Err(e) => {
+            server_utils::emit_notification(app, "error exporting setup".to_string()).await;
+            Err(format!("Failed to export setup: {}", e))
+        }
+    }
+}
+
+pub async fn import_command(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    {
+        let mut dialog_active = state.dialog_active.lock().unwrap();
+        *dialog_active = true;
+    }
+    
+    let file_path = app
+        .dialog()
+        .file()
+        .add_filter("Boxts Setup", &["zip"])
+        .blocking_pick_file();
+    
+    {
+        let mut dialog_active = state.dialog_active.lock().unwrap();
+        *dialog_active = false;
+    }
+    
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+
+    let path = match file_path {
+        Some(path) => path.into_path().map_err(|e| format!("Invalid import path: {}", e))?,
+        None => return Ok("Import cancelled".to_string()),
+    };
+
+    crate::log::tauri_log(&format!("Importing setup bundle from: {:?}", path));
+    server_utils::emit_notification(app.clone(), "importing setup".to_string()).await;
+
+    let current = state.config.lock().unwrap().clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        bundle::import_bundle(&path, &current)
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {}", e))?;
+
+    let summary = match result {
+        Ok(summary) => summary,
+        Err(e) => {
+            server_utils::emit_notification(app, "error importing setup".to_string()).await;
+            return Err(format!("Failed to import setup: {}", e));
+        }
+    };
+
+    for (original, renamed) in &summary.voices_renamed {
+        crate::log::tauri_log(&format!("Voice {} already exists, imported as {}", original, renamed));
+    }
+
+    config::replace_config(app.clone(), state, summary.config).await
+        .map_err(|e| format!("Failed to apply imported config: {}", e))?;
+
+    let message = format!(
+        "setup imported • {} voices added, {} renamed, {} unchanged, {} model files",
+        summary.voices_added, summary.voices_renamed.len(), summary.voices_skipped, summary.models_added
+    );
+    server_utils::emit_notification(app, message.clone()).await;
+    Ok(message)
+}
\ No newline at end of file
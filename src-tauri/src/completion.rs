@@ -0,0 +1,192 @@
+use serde::Serialize;
+use tauri::State;
+use crate::{AppState, aliases};
+
+// Offsets are in UTF-16 code units, which is what the input element's cursor uses
+#[derive(Serialize, Default)]
+pub struct CompletionResult {
+    pub start: usize,
+    pub end: usize,
+    pub candidates: Vec<String>,
+}
+
+fn byte_index(text: &str, utf16_offset: usize) -> usize {
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        if units >= utf16_offset {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+fn utf16_offset(text: &str, byte_index: usize) -> usize {
+    text[..byte_index].encode_utf16().count()
+}
+
+// Prefix matches beat substring matches, which beat scattered subsequence matches
+fn fuzzy_score(candidate: &str, query: &str) -> Option<usize> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate = candidate.to_lowercase();
+    let query = query.to_lowercase();
+    if candidate.starts_with(&query) {
+        return Some(300);
+    }
+    if let Some(position) = candidate.find(&query) {
+        return Some(200 - position.min(99));
+    }
+
+    let mut gaps = 0;
+    let mut query_chars = query.chars().peekable();
+    for c in candidate.chars() {
+        match query_chars.peek() {
+            Some(&next) if next == c => {
+                query_chars.next();
+            },
+            Some(_) => gaps += 1,
+            None => break,
+        }
+    }
+    match query_chars.peek() {
+        None => Some(100 - gaps.min(99)),
+        Some(_) => None,
+    }
+}
+
+// Keeps the provider's order among equal scores, so current values stay first
+fn rank(candidates: Vec<String>, query: &str) -> Vec<String> {
+    let mut scored: Vec<(usize, String)> = candidates
+        .into_iter()
+        .filter_map(|candidate| fuzzy_score(&candidate, query).map(|score| (score, candidate)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut ranked: Vec<String> = Vec::new();
+    for (_, candidate) in scored {
+        if !ranked.contains(&candidate) {
+            ranked.push(candidate);
+        }
+    }
+    ranked
+}
+
+pub fn complete(app: &tauri::AppHandle, state: &State<AppState>, text: &str, cursor: usize) -> CompletionResult {
+    let cursor = byte_index(text, cursor);
+    let before = &text[..cursor];
+
+    // Only the chained step under the cursor matters
+    let step_start = before.rfind(';').map(|index| index + 1).unwrap_or(0);
+    let step_end = text[cursor..].find(';').map(|index| cursor + index).unwrap_or(text.len());
+    let step_offset = step_start + (text[step_start..].len() - text[step_start..].trim_start().len());
+    let step = &text[step_offset.min(cursor)..cursor];
+
+    let command_line = match step.strip_prefix('/') {
+        Some(command_line) => command_line,
+        None => return CompletionResult::default(),
+    };
+    let command_start = step_offset + 1;
+
+    let (start, end, query, candidates) = match command_line.split_once(' ') {
+        None => {
+            // Completing the command name itself
+            let word_end = text[cursor..step_end]
+                .find(' ')
+                .map(|index| cursor + index)
+                .unwrap_or(step_end);
+            let mut names = state.commands.names();
+            names.extend(aliases::alias_names(state));
+            (command_start, word_end, command_line, names)
+        },
+        Some((name, argument)) => {
+            let command = match state.commands.find(name) {
+                Some(command) => command,
+                None => return CompletionResult::default(),
+            };
+            let completion = command.complete_argument(app, argument);
+            if completion.offset > argument.len() || !argument.is_char_boundary(completion.offset) {
+                return CompletionResult::default();
+            }
+
+            // Values may contain spaces (device names), so they run to the end of the step
+            let argument_start = command_start + name.len() + 1;
+            let start = argument_start + completion.offset;
+            (start, step_end, &argument[completion.offset..], completion.values)
+        },
+    };
+
+    CompletionResult {
+        start: utf16_offset(text, start),
+        end: utf16_offset(text, text[..end].trim_end().len().max(cursor)),
+        candidates: rank(candidates, query),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "é" is one UTF-16 unit and two bytes, "🔊" is two units and four bytes
+    const MIXED: &str = "/say é🔊 ok";
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn converts_utf16_offsets_to_byte_indices() {
+        assert_eq!(byte_index(MIXED, 0), 0);
+        assert_eq!(byte_index(MIXED, 5), 5);
+        assert_eq!(byte_index(MIXED, 6), 7);
+        assert_eq!(byte_index(MIXED, 8), 11);
+        assert_eq!(byte_index(MIXED, 100), MIXED.len());
+    }
+
+    #[test]
+    fn converts_byte_indices_back_to_utf16_offsets() {
+        for offset in [0, 5, 6, 8, 11] {
+            assert_eq!(utf16_offset(MIXED, byte_index(MIXED, offset)), offset);
+        }
+        assert_eq!(utf16_offset(MIXED, MIXED.len()), 11);
+    }
+
+    #[test]
+    fn scores_prefix_over_substring_over_subsequence() {
+        let prefix = fuzzy_score("voice", "vo").unwrap();
+        let substring = fuzzy_score("nextvoice", "vo").unwrap();
+        let subsequence = fuzzy_score("volume", "vme").unwrap();
+        assert!(prefix > substring);
+        assert!(substring > subsequence);
+        assert_eq!(fuzzy_score("voice", "xyz"), None);
+    }
+
+    #[test]
+    fn scores_ignore_case_and_accept_empty_queries() {
+        assert_eq!(fuzzy_score("OutputDevice", "output"), Some(300));
+        assert_eq!(fuzzy_score("anything", ""), Some(0));
+    }
+
+    #[test]
+    fn earlier_substrings_and_tighter_subsequences_score_higher() {
+        assert!(fuzzy_score("a_vo", "vo") > fuzzy_score("abcd_vo", "vo"));
+        assert!(fuzzy_score("vxlume", "vl") > fuzzy_score("vxxxlume", "vl"));
+    }
+
+    #[test]
+    fn ranks_by_score_and_keeps_order_among_equals() {
+        let candidates = strings(&["video_output", "nextvoice", "volume", "voice"]);
+        assert_eq!(rank(candidates, "vo"), strings(&["volume", "voice", "nextvoice", "video_output"]));
+
+        let candidates = strings(&["voice", "volume"]);
+        assert_eq!(rank(candidates, "vo"), strings(&["voice", "volume"]));
+    }
+
+    #[test]
+    fn ranking_drops_misses_and_duplicates() {
+        let candidates = strings(&["Default", "Headset", "Default", "Speakers"]);
+        assert_eq!(rank(candidates, "de"), strings(&["Default", "Headset"]));
+    }
+}
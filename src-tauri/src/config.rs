@@ -1,25 +1,271 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use tauri::State;
+use crate::writer::DebouncedWriter;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+const BACKUP_COUNT: usize = 3;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    IoError(std::io::Error),
+    ParseError(toml::de::Error),
+    SerializeError(toml::ser::Error),
+    WriterClosed,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::IoError(err) => write!(f, "IO Error: {}", err),
+            ConfigError::ParseError(err) => write!(f, "Parse Error: {}", err),
+            ConfigError::SerializeError(err) => write!(f, "Serialize Error: {}", err),
+            ConfigError::WriterClosed => write!(f, "Config writer is not running"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(error: std::io::Error) -> Self {
+        ConfigError::IoError(error)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(error: toml::de::Error) -> Self {
+        ConfigError::ParseError(error)
+    }
+}
+
+impl From<toml::ser::Error> for ConfigError {
+    fn from(error: toml::ser::Error) -> Self {
+        ConfigError::SerializeError(error)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct BoxtsConfig {
     pub window: WindowConfig,
     pub tts: TTSConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub hotkeys: HotkeyConfig,
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    // Alias name (without the slash) to the commands it runs, separated by ';'
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    // Snippet name to the text /say speaks, with {time}, {date}, {clipboard} and {voice} filled in
+    #[serde(default)]
+    pub snippets: BTreeMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct WindowConfig {
+    // Anchor on the monitor's work area: center, an edge (top, left, ...) or a corner (topleft, ...)
     pub position: String,
+    // Distance from the anchor towards the middle, in logical pixels ("10px") or percent of the work area ("5%")
+    #[serde(default = "default_window_offset")]
+    pub offset_x: String,
+    #[serde(default = "default_window_offset")]
+    pub offset_y: String,
+    // Index into the connected monitors, only used when `monitor` can't be found
     pub monitor_id: u32,
     pub lost_focus_behaviour: String,
+    // Show the overlay on the monitor under the mouse instead of the saved one
+    #[serde(default)]
+    pub follow_cursor: bool,
+    // The monitor the overlay belongs on, recognised by name and geometry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<MonitorIdentity>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+// Physical position and size of the whole monitor, not its work area
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MonitorIdentity {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn default_window_offset() -> String {
+    "10px".to_string()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OutputDeviceConfig {
+    // A device name as cpal lists it, or "Default" for the system default
+    pub name: String,
+    // Scales the global and per-voice volume on this device only
+    #[serde(default = "default_device_volume")]
+    pub volume: f32,
+}
+
+fn default_device_volume() -> f32 {
+    1.0
+}
+
+fn default_output_devices() -> Vec<OutputDeviceConfig> {
+    vec![OutputDeviceConfig {
+        name: "Default".to_string(),
+        volume: default_device_volume(),
+    }]
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct TTSConfig {
-    pub output_device: String,
+    // Every utterance plays on all of these at once
+    #[serde(default = "default_output_devices")]
+    pub output_devices: Vec<OutputDeviceConfig>,
+    // The single device older configs saved; read once and moved into output_devices
+    #[serde(default, skip_serializing)]
+    output_device: Option<String>,
     pub volume: f32,
     pub voice: String,
+    // Keyed by voice name without the .json extension so /set can address entries
+    #[serde(default)]
+    pub voice_settings: BTreeMap<String, VoiceSettings>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct HistoryConfig {
+    // Turning this off also wipes what was recorded so far
+    pub enabled: bool,
+    // Per kind, so lots of commands don't push out spoken lines
+    pub max_entries: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 500,
+        }
+    }
+}
+
+// System-wide shortcuts, written like "Alt+Enter" or "Ctrl+Shift+F9"; empty means unbound
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    pub toggle: String,
+    pub stop: String,
+    pub skip: String,
+    pub repeat: String,
+    pub mute: String,
+    pub next_voice: String,
+    pub clipboard: String,
+}
+
+// Only the overlay is bound out of the box, the rest would grab keys other apps may want
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            toggle: "Alt+Enter".to_string(),
+            stop: String::new(),
+            skip: String::new(),
+            repeat: String::new(),
+            mute: String::new(),
+            next_voice: String::new(),
+            clipboard: String::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    // Speak any new text that gets copied
+    pub watch: bool,
+    // In characters; longer text is not spoken
+    pub max_length: usize,
+    // The same text copied again within this many seconds is only spoken once
+    pub dedup_seconds: u64,
+    // Regular expressions; watched text matching any of them is never spoken
+    pub ignore: Vec<String>,
+}
+
+// One unbroken run of 20 or more characters: passwords, keys, tokens, hashes.
+// Short words with digits such as mp3 or COVID19 are still spoken.
+const SECRET_PATTERN: &str = r"^\s*\S{20,}\s*$";
+// The earlier default, which caught any single word mixing letters and digits
+const OLD_SECRET_PATTERN: &str = r"^\s*(\S*[A-Za-z]\S*[0-9]|\S*[0-9]\S*[A-Za-z])\S*\s*$";
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            watch: false,
+            max_length: 1000,
+            dedup_seconds: 60,
+            ignore: vec![
+                // Links
+                r"^\s*(https?|ftp)://\S+\s*$".to_string(),
+                SECRET_PATTERN.to_string(),
+            ],
+        }
+    }
+}
+
+// Overlay look; colors are #rrggbb, sizes are logical pixels
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ThemeConfig {
+    // The preset the colors came from, see /theme
+    pub preset: String,
+    pub background: String,
+    pub text: String,
+    pub border: String,
+    pub suggestion: String,
+    pub error: String,
+    pub item_primary: String,
+    pub item_secondary: String,
+    // Of the background only, text stays opaque
+    pub opacity: f32,
+    pub width: u32,
+    pub height: u32,
+    // Height of the multi-line editor opened with /expand
+    pub expanded_height: u32,
+    pub font_scale: f32,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        crate::theme::default_theme()
+    }
+}
+
+// Defaults mirror CoquiEngine's own, so an untouched entry sounds like before
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct VoiceSettings {
+    pub speed: f32,
+    pub temperature: f32,
+    pub language: String,
+    pub repetition_penalty: f32,
+    // Multiplied with tts.volume
+    pub volume: f32,
+}
+
+impl Default for VoiceSettings {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            temperature: 0.85,
+            language: "en".to_string(),
+            repetition_penalty: 7.0,
+            volume: 1.0,
+        }
+    }
 }
 
 impl Default for BoxtsConfig {
@@ -27,14 +273,26 @@ impl Default for BoxtsConfig {
         Self {
             window: WindowConfig {
                 position: "topleft".to_string(),
+                offset_x: default_window_offset(),
+                offset_y: default_window_offset(),
                 monitor_id: 0,
                 lost_focus_behaviour: "hide".to_string(),
+                follow_cursor: false,
+                monitor: None,
             },
             tts: TTSConfig {
-                output_device: "Default".to_string(),
+                output_devices: default_output_devices(),
+                output_device: None,
                 volume: 0.5,
                 voice: "Default".to_string(),
+                voice_settings: BTreeMap::new(),
             },
+            history: HistoryConfig::default(),
+            hotkeys: HotkeyConfig::default(),
+            clipboard: ClipboardConfig::default(),
+            theme: ThemeConfig::default(),
+            aliases: BTreeMap::new(),
+            snippets: BTreeMap::new(),
         }
     }
 }
@@ -51,27 +309,132 @@ fn get_config_path() -> PathBuf {
     }
 }
 
-pub fn load_config() -> Result<BoxtsConfig, confy::ConfyError> {
-    confy::load_path(get_config_path())
+fn get_sibling_path(suffix: &str) -> PathBuf {
+    let path = get_config_path();
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(suffix);
+    path.with_file_name(file_name)
+}
+
+fn get_backup_path(index: usize) -> PathBuf {
+    get_sibling_path(&format!(".bak{}", index))
+}
+
+fn read_config_file(path: &Path) -> Result<BoxtsConfig, ConfigError> {
+    let contents = fs::read_to_string(path)?;
+    let mut config: BoxtsConfig = toml::from_str(&contents)?;
+    if let Some(name) = config.tts.output_device.take() {
+        config.tts.output_devices = vec![OutputDeviceConfig {
+            name,
+            volume: default_device_volume(),
+        }];
+    }
+    for pattern in config.clipboard.ignore.iter_mut().filter(|pattern| *pattern == OLD_SECRET_PATTERN) {
+        *pattern = SECRET_PATTERN.to_string();
+    }
+    Ok(config)
+}
+
+fn replace_config_file(config: &BoxtsConfig) -> Result<(), ConfigError> {
+    let path = get_config_path();
+    let temp_path = get_sibling_path(".tmp");
+    let contents = toml::to_string_pretty(config)?;
+    
+    {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+    }
+    
+    // Only rotate a file that still parses, so a corrupt one never pushes out a good backup
+    if read_config_file(&path).is_ok() {
+        for index in (1..BACKUP_COUNT).rev() {
+            let from = get_backup_path(index);
+            if from.exists() {
+                fs::rename(&from, get_backup_path(index + 1))?;
+            }
+        }
+        fs::copy(&path, get_backup_path(1))?;
+    }
+    
+    fs::rename(&temp_path, &path)?;
+    Ok(())
 }
 
-pub fn save_config(config: &BoxtsConfig) -> Result<(), confy::ConfyError> {
-    confy::store_path(get_config_path(), config)
+// Writes to a temp file and renames it over the real one, so a crash leaves either the old or the new file.
+// A failed attempt removes the temp file again instead of leaving it next to the config.
+fn write_config_file(config: &BoxtsConfig) -> Result<(), ConfigError> {
+    let result = replace_config_file(config);
+    if result.is_err() {
+        let _ = fs::remove_file(get_sibling_path(".tmp"));
+    }
+    result
+}
+
+pub fn load_config() -> Result<BoxtsConfig, ConfigError> {
+    let path = get_config_path();
+    
+    if !path.exists() {
+        let config = BoxtsConfig::default();
+        write_config_file(&config)?;
+        return Ok(config);
+    }
+    
+    match read_config_file(&path) {
+        Ok(config) => Ok(config),
+        Err(e) => {
+            crate::log::tauri_log(&format!("Config file is unreadable ({}), trying backups...", e));
+            
+            for index in 1..=BACKUP_COUNT {
+                if let Ok(config) = read_config_file(&get_backup_path(index)) {
+                    crate::log::tauri_log(&format!("Recovered config from backup {}", index));
+                    // Keep the broken file around for inspection before replacing it
+                    let _ = fs::rename(&path, get_sibling_path(".corrupt"));
+                    write_config_file(&config)?;
+                    return Ok(config);
+                }
+            }
+            
+            // Nothing to recover from; keep the broken file rather than letting defaults overwrite it
+            crate::log::tauri_log("No usable backup, starting from the default config");
+            fs::rename(&path, get_sibling_path(".corrupt"))?;
+            let config = BoxtsConfig::default();
+            write_config_file(&config)?;
+            Ok(config)
+        }
+    }
+}
+
+// Persists config snapshots in the background so callers never hold the config mutex across file I/O
+pub type ConfigWriter = DebouncedWriter<BoxtsConfig>;
+
+pub fn spawn_writer() -> ConfigWriter {
+    DebouncedWriter::spawn("config", |config| write_config_file(&config).map_err(|e| e.to_string()))
+}
+
+// Inside a command the change also becomes part of that command's undo step, see undo::record_command
+pub fn save_config(state: &State<crate::AppState>, config: &BoxtsConfig) -> Result<(), ConfigError> {
+    crate::undo::note_save(config);
+    state.config_writer.save(config.clone()).map_err(|_| ConfigError::WriterClosed)
+}
+
+// Waits until every queued change is on disk; the Python server reads the file directly
+pub async fn flush_config(state: &State<'_, crate::AppState>) {
+    state.config_writer.flush().await;
 }
 
 pub async fn apply_ui_config(app: tauri::AppHandle, state: State<'_, crate::AppState>) -> Result<WindowConfig, Box<dyn std::error::Error>> {
-    let config = load_config()?;
-    // Switch to the saved monitor first
-    let (_switch_result, new_monitor_id) = crate::utils::switch_to_monitor(app.clone(), config.window.monitor_id).await?;
-    let _ = set_monitor_id(&state, new_monitor_id);
-    // Then apply the saved position
-    crate::utils::apply_window_position(app, state, &config.window.position).await?;
+    let config = state.config.lock().unwrap().clone();
+    // Size first, so the anchor is worked out for the window as it will be
+    crate::theme::apply_theme(&app, &state)?;
+    // Moves to the saved monitor (or a stand-in while it is unplugged) and applies the saved position
+    crate::monitors::apply_saved_monitor(app, state).await?;
     Ok(config.window)
 }
 
-pub fn apply_tts_config() -> Result<TTSConfig, confy::ConfyError> {
-    let config = load_config()?;
-    Ok(config.tts)
+pub fn apply_tts_config(state: &State<crate::AppState>) -> TTSConfig {
+    let config = state.config.lock().unwrap();
+    config.tts.clone()
 }
 
 pub fn get_window_position(state: &State<crate::AppState>) -> String {
@@ -79,33 +442,101 @@ pub fn get_window_position(state: &State<crate::AppState>) -> String {
     config.window.position.clone()
 }
 
-pub fn set_window_position(state: &State<crate::AppState>, position: &str) -> Result<(), Box<dyn std::error::Error>> {
+pub fn get_window_offset(state: &State<crate::AppState>) -> (String, String) {
+    let config = state.config.lock().unwrap();
+    (config.window.offset_x.clone(), config.window.offset_y.clone())
+}
+
+pub fn set_window_offset(state: &State<crate::AppState>, offset_x: &str, offset_y: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = state.config.lock().unwrap();
+    config.window.offset_x = offset_x.to_string();
+    config.window.offset_y = offset_y.to_string();
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+    Ok(())
+}
+
+// Picking a preset also resets the offset to that preset's margin
+pub fn set_window_anchor(state: &State<crate::AppState>, position: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (offset_x, offset_y) = crate::layout::default_offset(position);
     let mut config = state.config.lock().unwrap();
     config.window.position = position.to_string();
-    save_config(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+    config.window.offset_x = offset_x.to_string();
+    config.window.offset_y = offset_y.to_string();
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+    Ok(())
+}
+
+pub fn get_follow_cursor(state: &State<crate::AppState>) -> bool {
+    let config = state.config.lock().unwrap();
+    config.window.follow_cursor
+}
+
+pub fn set_follow_cursor(state: &State<crate::AppState>, follow_cursor: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = state.config.lock().unwrap();
+    config.window.follow_cursor = follow_cursor;
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
     Ok(())
 }
 
-pub fn set_monitor_id(state: &State<crate::AppState>, monitor_id: u32) -> Result<(), Box<dyn std::error::Error>> {
+pub fn get_theme(state: &State<crate::AppState>) -> ThemeConfig {
+    let config = state.config.lock().unwrap();
+    config.theme.clone()
+}
+
+pub fn get_clipboard_config(state: &State<crate::AppState>) -> ClipboardConfig {
+    let config = state.config.lock().unwrap();
+    config.clipboard.clone()
+}
+
+pub fn set_clipboard_watch(state: &State<crate::AppState>, watch: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = state.config.lock().unwrap();
+    config.clipboard.watch = watch;
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+    Ok(())
+}
+
+pub fn set_monitor(state: &State<crate::AppState>, monitor_id: u32, monitor: MonitorIdentity) -> Result<(), Box<dyn std::error::Error>> {
     let mut config = state.config.lock().unwrap();
     config.window.monitor_id = monitor_id;
-    save_config(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+    config.window.monitor = Some(monitor);
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
     Ok(())
 }
 
 
-pub fn get_output_device(state: &State<crate::AppState>) -> String {
+pub fn get_output_devices(state: &State<crate::AppState>) -> Vec<OutputDeviceConfig> {
     let config = state.config.lock().unwrap();
-    config.tts.output_device.clone()
+    config.tts.output_devices.clone()
 }
 
-pub fn set_output_device(state: &State<crate::AppState>, device_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+// Devices that were already selected keep their volume
+pub fn set_output_devices(state: &State<crate::AppState>, device_names: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     let mut config = state.config.lock().unwrap();
-    config.tts.output_device = device_name.to_string();
-    save_config(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+    let devices = device_names
+        .iter()
+        .map(|name| OutputDeviceConfig {
+            name: name.clone(),
+            volume: config.tts.output_devices
+                .iter()
+                .find(|device| &device.name == name)
+                .map_or(default_device_volume(), |device| device.volume),
+        })
+        .collect();
+    config.tts.output_devices = devices;
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
     Ok(())
 }
 
+pub fn set_output_device_volume(state: &State<crate::AppState>, device_name: &str, volume: f32) -> Result<(), String> {
+    let mut config = state.config.lock().unwrap();
+    let device = config.tts.output_devices
+        .iter_mut()
+        .find(|device| device.name == device_name)
+        .ok_or_else(|| format!("{} is not a selected output device", device_name))?;
+    device.volume = volume;
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))
+}
+
 pub fn get_volume(state: &State<crate::AppState>) -> f32 {
     let config = state.config.lock().unwrap();
     config.tts.volume
@@ -114,7 +545,7 @@ pub fn get_volume(state: &State<crate::AppState>) -> f32 {
 pub fn set_volume(state: &State<crate::AppState>, volume: f32) -> Result<(), Box<dyn std::error::Error>> {
     let mut config = state.config.lock().unwrap();
     config.tts.volume = volume;
-    save_config(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
     Ok(())
 }
 
@@ -126,13 +557,39 @@ pub fn get_voice(state: &State<crate::AppState>) -> Result<String, Box<dyn std::
 pub fn set_voice(state: &State<crate::AppState>, voice: &str) -> Result<(), Box<dyn std::error::Error>> {
     let mut config = state.config.lock().unwrap();
     config.tts.voice = voice.to_string();
-    save_config(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
     Ok(())
 }
 
+pub fn voice_settings_key(voice: &str) -> String {
+    voice.trim_end_matches(".json").to_string()
+}
+
+pub fn get_voice_settings(state: &State<crate::AppState>, voice: &str) -> VoiceSettings {
+    let config = state.config.lock().unwrap();
+    config.tts.voice_settings
+        .get(&voice_settings_key(voice))
+        .cloned()
+        .unwrap_or_default()
+}
+
+// Adds a default entry the first time a voice is used so it shows up for /set
+pub fn ensure_voice_settings(state: &State<crate::AppState>, voice: &str) -> Result<VoiceSettings, Box<dyn std::error::Error>> {
+    let mut config = state.config.lock().unwrap();
+    let key = voice_settings_key(voice);
+    if let Some(settings) = config.tts.voice_settings.get(&key) {
+        return Ok(settings.clone());
+    }
+    
+    let settings = VoiceSettings::default();
+    config.tts.voice_settings.insert(key, settings.clone());
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+    Ok(settings)
+}
+
 pub async fn apply_config(app: tauri::AppHandle, state: State<'_, crate::AppState>) -> Result<(), Box<dyn std::error::Error>> {
     apply_ui_config(app.clone(), state.clone()).await?;
-    apply_tts_config()?;
+    apply_tts_config(&state);
     Ok(())
 }
 
@@ -144,20 +601,25 @@ pub fn get_lostfocus_behaviour(state: &State<crate::AppState>) -> String {
 pub fn set_lostfocus_behaviour(state: &State<crate::AppState>, behaviour: &str) -> Result<(), Box<dyn std::error::Error>> {
     let mut config = state.config.lock().unwrap();
     config.window.lost_focus_behaviour = behaviour.to_string();
-    save_config(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+    save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
     Ok(())
 }
 
 pub async fn reset_config(app: tauri::AppHandle, state: State<'_, crate::AppState>) -> Result<(), Box<dyn std::error::Error>> {
-    let default_config = BoxtsConfig::default();
+    replace_config(app, state, BoxtsConfig::default()).await
+}
 
-    {
+// Swaps in a whole config and pushes whatever differs to the window, the hotkeys and the backend
+pub async fn replace_config(app: tauri::AppHandle, state: State<'_, crate::AppState>, new_config: BoxtsConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let changed = {
         let mut config = state.config.lock().unwrap();
-        *config = default_config.clone();
-        save_config(&config).map_err(|e| format!("Failed to save config: {}", e))?;
-    }
+        let changed = crate::settings::changed_keys(&config, &new_config);
+        *config = new_config;
+        save_config(&state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+        changed
+    };
     
-    apply_config(app, state).await?;
+    crate::settings::apply_changes(app, state, &changed).await?;
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,69 @@
+use serde::Serialize;
+use tauri::{Manager, State};
+use crate::{AppState, hotkeys};
+use crate::registry::{self, ArgumentCompletion, Command};
+
+// Keys handled by the overlay itself rather than by a command
+const HOTKEYS: &[(&str, &str)] = &[
+    ("Enter", "Speak the text or run the command"),
+    ("Tab", "Complete the command or argument, press again to cycle"),
+    ("Up / Down", "Recall earlier lines, or move through an item selector"),
+    ("Ctrl+R", "Search everything typed so far"),
+    ("Space", "Tick or untick the item in a selector that takes several, e.g. /outputdevice"),
+    ("Escape", "Close an item selector or a search"),
+    ("Alt+Drag", "Move the overlay; the new position is remembered"),
+    ("Ctrl+E", "Switch to the multi-line editor and back"),
+    ("Ctrl+Enter", "Speak everything in the multi-line editor as one utterance"),
+];
+
+#[derive(Serialize)]
+pub struct HotkeyInfo {
+    pub keys: String,
+    pub description: String,
+}
+
+// The global shortcuts as currently bound, then the overlay's own keys
+pub fn hotkeys(state: &State<AppState>) -> Vec<HotkeyInfo> {
+    let bound = state.config.lock().unwrap().hotkeys.clone();
+    let global = hotkeys::HOTKEY_ACTIONS.iter().filter_map(|(action, description)| {
+        hotkeys::binding(&bound, action)
+            .filter(|keys| !keys.trim().is_empty() && keys.trim() != "none")
+            .map(|keys| HotkeyInfo {
+                keys: keys.to_string(),
+                description: format!("{} (from anywhere, /hotkey {} to rebind)", description, action),
+            })
+    });
+
+    global
+        .chain(HOTKEYS.iter().map(|(keys, description)| HotkeyInfo {
+            keys: keys.to_string(),
+            description: description.to_string(),
+        }))
+        .collect()
+}
+
+// One line for the overlay, e.g. "/volume <volume> - Set TTS volume • current: 0.50 • e.g. /volume 0.3"
+pub fn describe_command(app: &tauri::AppHandle, command: &dyn Command) -> String {
+    let mut parts = vec![format!("{} - {}", registry::usage(command), command.description())];
+
+    if let Some(current) = command.current_value(app) {
+        parts.push(format!("current: {}", current));
+    }
+    let aliases = command.aliases();
+    if !aliases.is_empty() {
+        let aliases: Vec<String> = aliases.iter().map(|alias| format!("/{}", alias)).collect();
+        parts.push(format!("also {}", aliases.join(", ")));
+    }
+    if let Some(example) = command.examples().first() {
+        parts.push(format!("e.g. {}", example));
+    }
+
+    parts.join(" • ")
+}
+
+pub fn complete_argument(app: &tauri::AppHandle, _argument: &str) -> ArgumentCompletion {
+    ArgumentCompletion {
+        offset: 0,
+        values: app.state::<AppState>().commands.names(),
+    }
+}
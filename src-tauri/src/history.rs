@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+use crate::AppState;
+use crate::writer::DebouncedWriter;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryKind {
+    Text,
+    Command,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    pub kind: HistoryKind,
+    pub text: String,
+}
+
+// Oldest entry first, both kinds interleaved in the order they were entered
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    // A line is recorded on every submit, so the file is written at most once per burst
+    writer: DebouncedWriter<Vec<HistoryEntry>>,
+}
+
+fn get_history_path() -> PathBuf {
+    if cfg!(debug_assertions) {
+        PathBuf::from("../boxts.history.json")
+    } else {
+        std::env::current_exe()
+            .unwrap_or_else(|_| PathBuf::from("./boxts.exe"))
+            .parent()
+            .unwrap_or(&PathBuf::from("."))
+            .join("boxts.history.json")
+    }
+}
+
+impl History {
+    pub fn load() -> Self {
+        let entries = fs::read_to_string(get_history_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { entries, writer: DebouncedWriter::spawn("history", write_history_file) }
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.writer.save(self.entries.clone()) {
+            crate::log::tauri_log(&format!("Failed to save history: {}", e));
+        }
+    }
+
+    fn trim(&mut self, max_entries: usize) {
+        for kind in [HistoryKind::Text, HistoryKind::Command] {
+            let count = self.entries.iter().filter(|entry| entry.kind == kind).count();
+            let mut excess = count.saturating_sub(max_entries);
+            self.entries.retain(|entry| {
+                if excess > 0 && entry.kind == kind {
+                    excess -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+
+    pub fn record(&mut self, kind: HistoryKind, text: &str, max_entries: usize) {
+        // Re-entering a line moves it to the front instead of storing it twice
+        self.entries.retain(|entry| entry.kind != kind || entry.text != text);
+        self.entries.push(HistoryEntry { kind, text: text.to_string() });
+        self.trim(max_entries);
+        self.save();
+    }
+
+    pub fn apply_limit(&mut self, max_entries: usize) {
+        self.trim(max_entries);
+        self.save();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        let removed = self.writer.discard(|| {
+            let _ = fs::remove_file(get_history_path());
+        });
+        if let Err(e) = removed {
+            crate::log::tauri_log(&format!("Failed to clear history: {}", e));
+        }
+    }
+
+    // Index 0 is the most recent entry of that kind
+    pub fn recall(&self, kind: HistoryKind, index: usize) -> Option<String> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.kind == kind)
+            .nth(index)
+            .map(|entry| entry.text.clone())
+    }
+
+    // Newest match first; `skip` steps back to older matches like repeated Ctrl+R
+    pub fn search(&self, query: &str, kind: Option<HistoryKind>, skip: usize) -> Option<String> {
+        let query = query.to_lowercase();
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| kind.is_none() || kind == Some(entry.kind))
+            .filter(|entry| entry.text.to_lowercase().contains(&query))
+            .nth(skip)
+            .map(|entry| entry.text.clone())
+    }
+}
+
+fn write_history_file(entries: Vec<HistoryEntry>) -> Result<(), String> {
+    let path = get_history_path();
+    let temp_path = path.with_extension("json.tmp");
+    let data = serde_json::to_vec(&entries).map_err(|e| e.to_string())?;
+    fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, &path).map_err(|e| e.to_string())
+}
+
+// Waits until the last recorded line is on disk, for quitting
+pub async fn flush_history(state: &State<'_, AppState>) {
+    let writer = state.history.lock().unwrap().writer.clone();
+    writer.flush().await;
+}
+
+pub fn record(state: &State<AppState>, text: &str) {
+    let (enabled, max_entries) = {
+        let config = state.config.lock().unwrap();
+        (config.history.enabled, config.history.max_entries)
+    };
+    if !enabled || text.trim().is_empty() {
+        return;
+    }
+
+    let kind = if text.starts_with('/') {
+        HistoryKind::Command
+    } else {
+        HistoryKind::Text
+    };
+    state.history.lock().unwrap().record(kind, text.trim(), max_entries);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> History {
+        // Never touches the real history file
+        History { entries: Vec::new(), writer: DebouncedWriter::spawn("test history", |_| Ok(())) }
+    }
+
+    fn texts(history: &History, kind: HistoryKind) -> Vec<String> {
+        (0..).map_while(|index| history.recall(kind, index)).collect()
+    }
+
+    #[test]
+    fn recall_walks_back_through_one_kind() {
+        let mut history = history();
+        history.record(HistoryKind::Text, "hello", 10);
+        history.record(HistoryKind::Command, "/stop", 10);
+        history.record(HistoryKind::Text, "bye", 10);
+
+        assert_eq!(texts(&history, HistoryKind::Text), vec!["bye", "hello"]);
+        assert_eq!(texts(&history, HistoryKind::Command), vec!["/stop"]);
+        assert_eq!(history.recall(HistoryKind::Command, 1), None);
+    }
+
+    #[test]
+    fn recording_a_line_again_moves_it_to_the_front() {
+        let mut history = history();
+        history.record(HistoryKind::Text, "hello", 10);
+        history.record(HistoryKind::Text, "bye", 10);
+        history.record(HistoryKind::Text, "hello", 10);
+
+        assert_eq!(texts(&history, HistoryKind::Text), vec!["hello", "bye"]);
+    }
+
+    #[test]
+    fn the_limit_applies_to_each_kind_separately() {
+        let mut history = history();
+        for line in ["one", "two", "three"] {
+            history.record(HistoryKind::Text, line, 2);
+        }
+        history.record(HistoryKind::Command, "/stop", 2);
+
+        assert_eq!(texts(&history, HistoryKind::Text), vec!["three", "two"]);
+        assert_eq!(texts(&history, HistoryKind::Command), vec!["/stop"]);
+
+        history.apply_limit(1);
+        assert_eq!(texts(&history, HistoryKind::Text), vec!["three"]);
+        assert_eq!(texts(&history, HistoryKind::Command), vec!["/stop"]);
+    }
+
+    #[test]
+    fn search_is_case_insensitive_newest_first_and_can_skip() {
+        let mut history = history();
+        history.record(HistoryKind::Text, "Good morning", 10);
+        history.record(HistoryKind::Command, "/say morning", 10);
+        history.record(HistoryKind::Text, "good night", 10);
+
+        assert_eq!(history.search("GOOD", None, 0), Some("good night".to_string()));
+        assert_eq!(history.search("good", None, 1), Some("Good morning".to_string()));
+        assert_eq!(history.search("good", None, 2), None);
+        assert_eq!(history.search("morning", None, 0), Some("/say morning".to_string()));
+        assert_eq!(history.search("morning", Some(HistoryKind::Text), 0), Some("Good morning".to_string()));
+        assert_eq!(history.search("evening", None, 0), None);
+    }
+}
@@ -0,0 +1,317 @@
+use std::str::FromStr;
+use tauri::{Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use crate::{AppState, config, monitors};
+use crate::config::HotkeyConfig;
+use crate::registry::ArgumentCompletion;
+
+// Actions that can be bound to a system-wide shortcut, with what they do
+pub const HOTKEY_ACTIONS: &[(&str, &str)] = &[
+    ("toggle", "Show or hide the input overlay"),
+    ("stop", "Stop speaking and drop anything queued"),
+    ("skip", "Skip to the next queued text"),
+    ("repeat", "Speak the last text again"),
+    ("mute", "Mute or unmute speech"),
+    ("next_voice", "Change to the next voice"),
+    ("clipboard", "Speak the clipboard"),
+];
+
+const UNBOUND: &str = "none";
+
+pub fn binding<'a>(hotkeys: &'a HotkeyConfig, action: &str) -> Option<&'a str> {
+    match action {
+        "toggle" => Some(&hotkeys.toggle),
+        "stop" => Some(&hotkeys.stop),
+        "skip" => Some(&hotkeys.skip),
+        "repeat" => Some(&hotkeys.repeat),
+        "mute" => Some(&hotkeys.mute),
+        "next_voice" => Some(&hotkeys.next_voice),
+        "clipboard" => Some(&hotkeys.clipboard),
+        _ => None,
+    }
+}
+
+fn binding_mut<'a>(hotkeys: &'a mut HotkeyConfig, action: &str) -> Option<&'a mut String> {
+    match action {
+        "toggle" => Some(&mut hotkeys.toggle),
+        "stop" => Some(&mut hotkeys.stop),
+        "skip" => Some(&mut hotkeys.skip),
+        "repeat" => Some(&mut hotkeys.repeat),
+        "mute" => Some(&mut hotkeys.mute),
+        "next_voice" => Some(&mut hotkeys.next_voice),
+        "clipboard" => Some(&mut hotkeys.clipboard),
+        _ => None,
+    }
+}
+
+fn is_unbound(raw: &str) -> bool {
+    let raw = raw.trim();
+    raw.is_empty() || raw == UNBOUND
+}
+
+// Parses the shortcut `action` is about to get and makes sure no other action has it already;
+// None means `action` is being unbound
+pub fn check_binding(hotkeys: &HotkeyConfig, action: &str, raw: &str) -> Result<Option<Shortcut>, String> {
+    if binding(hotkeys, action).is_none() {
+        return Err(format!("Unknown hotkey action: {}", action));
+    }
+    if is_unbound(raw) {
+        return Ok(None);
+    }
+
+    let shortcut = parse_shortcut(raw)?;
+    let clash = HOTKEY_ACTIONS.iter().map(|(other, _)| *other).find(|other| {
+        *other != action && binding(hotkeys, other)
+            .and_then(|bound| parse_shortcut(bound).ok())
+            .is_some_and(|bound| bound.id() == shortcut.id())
+    });
+    match clash {
+        Some(other) => Err(format!("{} is already bound to {}", raw.trim(), other)),
+        None => Ok(Some(shortcut)),
+    }
+}
+
+fn parse_shortcut(raw: &str) -> Result<Shortcut, String> {
+    Shortcut::from_str(raw.trim())
+        .map_err(|e| format!("Invalid shortcut {}: {} (use e.g. Alt+Enter or Ctrl+Shift+F9)", raw.trim(), e))
+}
+
+// Registers every bound action from scratch and returns what went wrong, one line per action
+pub fn register_all(app: &tauri::AppHandle) -> Vec<String> {
+    let state = app.state::<AppState>();
+    let hotkeys = state.config.lock().unwrap().hotkeys.clone();
+    let global_shortcut = app.global_shortcut();
+    if let Err(e) = global_shortcut.unregister_all() {
+        crate::log::tauri_log(&format!("Failed to unregister hotkeys: {}", e));
+    }
+
+    let mut registered: Vec<(Shortcut, &str)> = Vec::new();
+    let mut problems = Vec::new();
+    for (action, _) in HOTKEY_ACTIONS {
+        let raw = binding(&hotkeys, action).unwrap_or_default().trim();
+        if is_unbound(raw) {
+            continue;
+        }
+
+        let shortcut = match parse_shortcut(raw) {
+            Ok(shortcut) => shortcut,
+            Err(e) => {
+                problems.push(format!("{}: {}", action, e));
+                continue;
+            }
+        };
+        if let Some((_, other)) = registered.iter().find(|(taken, _)| taken.id() == shortcut.id()) {
+            problems.push(format!("{}: {} is already used by {}", action, raw, other));
+            continue;
+        }
+        match global_shortcut.register(shortcut) {
+            Ok(()) => registered.push((shortcut, action)),
+            Err(e) => problems.push(format!("{}: {} could not be registered, another app may be using it ({})", action, raw, e)),
+        }
+    }
+
+    for problem in &problems {
+        crate::log::tauri_log(&format!("Hotkey problem: {}", problem));
+    }
+    *state.hotkey_problems.lock().unwrap() = problems.clone();
+    problems
+}
+
+pub fn handle_shortcut(app: &tauri::AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    let hotkeys = app.state::<AppState>().config.lock().unwrap().hotkeys.clone();
+    let action = HOTKEY_ACTIONS.iter().map(|(action, _)| *action).find(|action| {
+        binding(&hotkeys, action)
+            .and_then(|raw| parse_shortcut(raw).ok())
+            .is_some_and(|bound| bound.id() == shortcut.id())
+    });
+
+    if let Some(action) = action {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = run_action(app, action).await {
+                crate::log::tauri_log(&format!("Hotkey {} failed: {}", action, e));
+            }
+        });
+    }
+}
+
+async fn run_action(app: tauri::AppHandle, action: &str) -> Result<(), String> {
+    match action {
+        "toggle" => toggle_overlay(app).await,
+        "stop" => run_slash_command(app, "stopspeaking").await,
+        "skip" => run_slash_command(app, "skip").await,
+        "repeat" => run_slash_command(app, "repeat").await,
+        "mute" => run_slash_command(app, "mute").await,
+        "next_voice" => run_slash_command(app, "nextvoice").await,
+        "clipboard" => run_slash_command(app, "clipboard").await,
+        _ => Err(format!("Unknown hotkey action: {}", action)),
+    }
+}
+
+// Same path as typing the command, so a hotkey behaves exactly like its slash command
+async fn run_slash_command(app: tauri::AppHandle, command: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    crate::run_command(command, app.clone(), state).await.map(|_| ())
+}
+
+async fn toggle_overlay(app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    if *state.dialog_active.lock().unwrap() {
+        return Ok(());
+    }
+
+    let window = app.get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    if window.is_visible().unwrap_or(false) {
+        return window.hide().map_err(|e| format!("Failed to hide window: {}", e));
+    }
+
+    // Place the window before it appears so it doesn't jump between monitors
+    if config::get_follow_cursor(&state) {
+        if let Err(e) = monitors::apply_cursor_monitor(app.clone(), state.clone()).await {
+            crate::log::tauri_log(&format!("Failed to follow cursor: {}", e));
+        }
+    }
+    window.show().map_err(|e| format!("Failed to show window: {}", e))?;
+    window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
+    Ok(())
+}
+
+pub fn list_hotkeys(state: &State<AppState>) -> String {
+    let hotkeys = state.config.lock().unwrap().hotkeys.clone();
+    let mut lines: Vec<String> = HOTKEY_ACTIONS
+        .iter()
+        .map(|(action, _)| {
+            let raw = binding(&hotkeys, action).unwrap_or_default();
+            format!("{} = {}", action, if raw.trim().is_empty() { UNBOUND } else { raw })
+        })
+        .collect();
+    lines.extend(state.hotkey_problems.lock().unwrap().iter().map(|problem| format!("⚠ {}", problem)));
+    lines.join(" | ")
+}
+
+// Registers the bindings as the config has them now. When the shortcut for `action` can't be
+// taken, that binding goes back to `previous` in memory and the problem is returned.
+pub fn register_binding(app: &tauri::AppHandle, state: &State<AppState>, action: &str, previous: &str) -> Result<(), String> {
+    let prefix = format!("{}:", action);
+    match register_all(app).into_iter().find(|problem| problem.starts_with(&prefix)) {
+        None => Ok(()),
+        Some(problem) => {
+            if let Some(slot) = binding_mut(&mut state.config.lock().unwrap().hotkeys, action) {
+                *slot = previous.to_string();
+            }
+            register_all(app);
+            Err(problem)
+        }
+    }
+}
+
+// Binds `action` and registers it right away; a shortcut that can't be registered is rolled back
+pub fn bind(app: &tauri::AppHandle, state: &State<AppState>, action: &str, raw: &str) -> Result<String, String> {
+    let raw = raw.trim();
+    let unbind = is_unbound(raw);
+
+    let previous = {
+        let mut config = state.config.lock().unwrap();
+        check_binding(&config.hotkeys, action, raw)?;
+
+        let slot = binding_mut(&mut config.hotkeys, action)
+            .ok_or_else(|| format!("Unknown hotkey action: {}", action))?;
+        std::mem::replace(slot, if unbind { String::new() } else { raw.to_string() })
+    };
+
+    // Only saved once the shortcut is actually ours
+    register_binding(app, state, action, &previous)?;
+    {
+        let config = state.config.lock().unwrap();
+        config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+    }
+
+    if unbind {
+        Ok(format!("{} unbound", action))
+    } else {
+        Ok(format!("{} bound to {}", action, raw))
+    }
+}
+
+// Completes the action, then offers its current shortcut or none
+pub fn complete_argument(app: &tauri::AppHandle, argument: &str) -> ArgumentCompletion {
+    match argument.split_once(' ') {
+        None => {
+            let mut values = vec!["list".to_string()];
+            values.extend(HOTKEY_ACTIONS.iter().map(|(action, _)| action.to_string()));
+            ArgumentCompletion { offset: 0, values }
+        },
+        Some((action, _)) => {
+            let hotkeys = app.state::<AppState>().config.lock().unwrap().hotkeys.clone();
+            let mut values: Vec<String> = binding(&hotkeys, action)
+                .filter(|raw| !raw.trim().is_empty())
+                .map(|raw| vec![raw.to_string()])
+                .unwrap_or_default();
+            values.push(UNBOUND.to_string());
+            ArgumentCompletion { offset: action.len() + 1, values }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hotkeys(toggle: &str, stop: &str) -> HotkeyConfig {
+        HotkeyConfig {
+            toggle: toggle.to_string(),
+            stop: stop.to_string(),
+            ..HotkeyConfig::default()
+        }
+    }
+
+    #[test]
+    fn empty_and_none_unbind() {
+        let config = hotkeys("Alt+Enter", "");
+        for raw in ["", "  ", "none", " none "] {
+            assert!(check_binding(&config, "stop", raw).unwrap().is_none(), "{:?} should unbind", raw);
+        }
+    }
+
+    #[test]
+    fn accepts_a_free_shortcut() {
+        let config = hotkeys("Alt+Enter", "");
+        assert!(check_binding(&config, "stop", "Ctrl+Shift+F9").unwrap().is_some());
+    }
+
+    #[test]
+    fn rejects_a_shortcut_taken_by_another_action() {
+        let config = hotkeys("Alt+Enter", "Ctrl+F9");
+        assert_eq!(
+            check_binding(&config, "toggle", "Ctrl+F9").unwrap_err(),
+            "Ctrl+F9 is already bound to stop"
+        );
+        // Spelled differently, still the same keys
+        assert!(check_binding(&config, "skip", "control+f9").is_err());
+    }
+
+    #[test]
+    fn rebinding_an_action_to_its_own_shortcut_is_fine() {
+        let config = hotkeys("Alt+Enter", "Ctrl+F9");
+        assert!(check_binding(&config, "stop", "Ctrl+F9").unwrap().is_some());
+    }
+
+    #[test]
+    fn unbound_and_broken_bindings_clash_with_nothing() {
+        let config = hotkeys("none", "not a shortcut");
+        assert!(check_binding(&config, "skip", "Alt+Enter").is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_actions_and_invalid_shortcuts() {
+        let config = HotkeyConfig::default();
+        assert!(check_binding(&config, "launch", "Ctrl+F9").is_err());
+        assert!(check_binding(&config, "launch", "none").is_err());
+        assert!(check_binding(&config, "stop", "Ctrl+Nothing").is_err());
+    }
+}
@@ -0,0 +1,243 @@
+// Window placement as plain geometry, so it can be checked without a real monitor.
+// Everything here is in physical pixels except pixel offsets, which are logical and
+// get multiplied by the monitor's scale factor.
+
+pub const ANCHORS: &[&str] = &[
+    "center", "top", "bottom", "left", "right", "topleft", "topright", "bottomleft", "bottomright",
+];
+
+const OFFSET_HINT: &str = "use e.g. 5% or 40px";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+// Where the window sits along one axis of the work area
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anchor {
+    pub horizontal: Align,
+    pub vertical: Align,
+}
+
+pub fn parse_anchor(name: &str) -> Option<Anchor> {
+    let (horizontal, vertical) = match name {
+        "center" => (Align::Center, Align::Center),
+        "top" => (Align::Center, Align::Start),
+        "bottom" => (Align::Center, Align::End),
+        "left" => (Align::Start, Align::Center),
+        "right" => (Align::End, Align::Center),
+        "topleft" => (Align::Start, Align::Start),
+        "topright" => (Align::End, Align::Start),
+        "bottomleft" => (Align::Start, Align::End),
+        "bottomright" => (Align::End, Align::End),
+        _ => return None,
+    };
+    Some(Anchor { horizontal, vertical })
+}
+
+// The margin an anchor gets when it is picked with its own command: 10px from each edge it touches
+pub fn default_offset(anchor: &str) -> (&'static str, &'static str) {
+    let margin = |align: Align| if align == Align::Center { "0px" } else { "10px" };
+    match parse_anchor(anchor) {
+        Some(anchor) => (margin(anchor.horizontal), margin(anchor.vertical)),
+        None => ("10px", "10px"),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Offset {
+    // Logical pixels
+    Pixels(f64),
+    // Of the work area along the same axis
+    Percent(f64),
+}
+
+impl Offset {
+    fn to_physical(self, span: u32, scale: f64) -> i32 {
+        match self {
+            Offset::Pixels(pixels) => (pixels * scale).round() as i32,
+            Offset::Percent(percent) => (span as f64 * percent / 100.0).round() as i32,
+        }
+    }
+}
+
+// "40", "40px" or "5%"; negative values pull back towards the anchored edge
+pub fn parse_offset(raw: &str) -> Result<Offset, String> {
+    let raw = raw.trim();
+    if let Some(percent) = raw.strip_suffix('%') {
+        return percent
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|percent| percent.is_finite() && percent.abs() <= 100.0)
+            .map(Offset::Percent)
+            .ok_or_else(|| format!("Invalid offset: {} ({})", raw, OFFSET_HINT));
+    }
+
+    raw.strip_suffix("px")
+        .unwrap_or(raw)
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|pixels| pixels.is_finite())
+        .map(Offset::Pixels)
+        .ok_or_else(|| format!("Invalid offset: {} ({})", raw, OFFSET_HINT))
+}
+
+// Offsets point from the anchored edge towards the middle; on a centered axis positive means right or down
+fn place(start: i32, span: u32, size: u32, align: Align, offset: i32) -> i32 {
+    let free = span as i32 - size as i32;
+    let position = match align {
+        Align::Start => offset,
+        Align::Center => free / 2 + offset,
+        Align::End => free - offset,
+    };
+    // Keep the whole window on the work area; a window wider than it sticks to the start edge
+    start + position.clamp(0, free.max(0))
+}
+
+fn offset_from(start: i32, span: u32, size: u32, align: Align, position: i32) -> i32 {
+    let free = span as i32 - size as i32;
+    match align {
+        Align::Start => position - start,
+        Align::Center => position - start - free / 2,
+        Align::End => start + free - position,
+    }
+}
+
+// Top-left corner of the window in physical pixels
+pub fn window_position(work_area: Rect, window: Size, anchor: Anchor, offset: (Offset, Offset), scale: f64) -> (i32, i32) {
+    (
+        place(work_area.x, work_area.width, window.width, anchor.horizontal, offset.0.to_physical(work_area.width, scale)),
+        place(work_area.y, work_area.height, window.height, anchor.vertical, offset.1.to_physical(work_area.height, scale)),
+    )
+}
+
+// The logical pixel offsets that put the window back at `position`, e.g. after a drag
+pub fn offset_for_position(work_area: Rect, window: Size, anchor: Anchor, position: (i32, i32), scale: f64) -> (i32, i32) {
+    let logical = |physical: i32| (physical as f64 / scale).round() as i32;
+    (
+        logical(offset_from(work_area.x, work_area.width, window.width, anchor.horizontal, position.0)),
+        logical(offset_from(work_area.y, work_area.height, window.height, anchor.vertical, position.1)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Size = Size { width: 444, height: 54 };
+    // 1080p with a 40px taskbar at the bottom
+    const FULL_HD: Rect = Rect { x: 0, y: 0, width: 1920, height: 1040 };
+    // 4K at 200% to the left of the primary monitor, taskbar on top
+    const LEFT_4K: Rect = Rect { x: -3840, y: 80, width: 3840, height: 2080 };
+
+    fn margin(pixels: f64) -> (Offset, Offset) {
+        (Offset::Pixels(pixels), Offset::Pixels(pixels))
+    }
+
+    fn at(anchor: &str, work_area: Rect, offset: (Offset, Offset), scale: f64) -> (i32, i32) {
+        window_position(work_area, WINDOW, parse_anchor(anchor).unwrap(), offset, scale)
+    }
+
+    #[test]
+    fn parses_all_nine_anchors() {
+        for name in ANCHORS {
+            assert!(parse_anchor(name).is_some(), "{} should parse", name);
+        }
+        assert_eq!(ANCHORS.len(), 9);
+        assert!(parse_anchor("middle").is_none());
+    }
+
+    #[test]
+    fn parses_offsets() {
+        assert_eq!(parse_offset("40"), Ok(Offset::Pixels(40.0)));
+        assert_eq!(parse_offset("40px"), Ok(Offset::Pixels(40.0)));
+        assert_eq!(parse_offset(" -12.5px "), Ok(Offset::Pixels(-12.5)));
+        assert_eq!(parse_offset("5%"), Ok(Offset::Percent(5.0)));
+        assert!(parse_offset("150%").is_err());
+        assert!(parse_offset("abc").is_err());
+        assert!(parse_offset("").is_err());
+    }
+
+    #[test]
+    fn corners_keep_the_margin_inside_the_work_area() {
+        assert_eq!(at("topleft", FULL_HD, margin(10.0), 1.0), (10, 10));
+        assert_eq!(at("topright", FULL_HD, margin(10.0), 1.0), (1920 - 444 - 10, 10));
+        assert_eq!(at("bottomleft", FULL_HD, margin(10.0), 1.0), (10, 1040 - 54 - 10));
+        assert_eq!(at("bottomright", FULL_HD, margin(10.0), 1.0), (1920 - 444 - 10, 1040 - 54 - 10));
+    }
+
+    #[test]
+    fn edges_and_center_are_centered_on_the_other_axis() {
+        let none = margin(0.0);
+        assert_eq!(at("center", FULL_HD, none, 1.0), (738, 493));
+        assert_eq!(at("top", FULL_HD, none, 1.0), (738, 0));
+        assert_eq!(at("bottom", FULL_HD, none, 1.0), (738, 986));
+        assert_eq!(at("left", FULL_HD, none, 1.0), (0, 493));
+        assert_eq!(at("right", FULL_HD, none, 1.0), (1476, 493));
+    }
+
+    #[test]
+    fn pixel_margins_follow_the_scale_factor() {
+        // 10 logical pixels are 20 physical ones at 200%
+        assert_eq!(at("topleft", LEFT_4K, margin(10.0), 2.0), (-3840 + 20, 80 + 20));
+        assert_eq!(at("bottomright", LEFT_4K, margin(10.0), 2.0), (-444 - 20, 80 + 2080 - 54 - 20));
+        assert_eq!(at("topleft", FULL_HD, margin(10.0), 1.5), (15, 15));
+    }
+
+    #[test]
+    fn percent_offsets_use_the_work_area() {
+        let offset = (Offset::Percent(10.0), Offset::Percent(50.0));
+        assert_eq!(at("topleft", FULL_HD, offset, 2.0), (192, 520));
+        assert_eq!(at("bottomright", FULL_HD, offset, 1.0), (1920 - 444 - 192, 1040 - 54 - 520));
+    }
+
+    #[test]
+    fn never_leaves_the_work_area() {
+        assert_eq!(at("topleft", FULL_HD, margin(-50.0), 1.0), (0, 0));
+        assert_eq!(at("bottomright", FULL_HD, margin(5000.0), 1.0), (0, 0));
+        assert_eq!(at("center", FULL_HD, (Offset::Percent(100.0), Offset::Percent(-100.0)), 1.0), (1476, 0));
+
+        let tiny = Rect { x: 100, y: 100, width: 300, height: 40 };
+        assert_eq!(at("bottomright", tiny, margin(10.0), 1.0), (100, 100));
+    }
+
+    #[test]
+    fn dragged_position_round_trips() {
+        for name in ANCHORS {
+            let anchor = parse_anchor(name).unwrap();
+            for (work_area, scale) in [(FULL_HD, 1.0), (LEFT_4K, 2.0)] {
+                let position = window_position(work_area, WINDOW, anchor, margin(24.0), scale);
+                let (x, y) = offset_for_position(work_area, WINDOW, anchor, position, scale);
+                let offset = (Offset::Pixels(x as f64), Offset::Pixels(y as f64));
+                assert_eq!(window_position(work_area, WINDOW, anchor, offset, scale), position, "{} at {}x", name, scale);
+            }
+        }
+    }
+
+    #[test]
+    fn default_offsets_only_apply_to_touched_edges() {
+        assert_eq!(default_offset("center"), ("0px", "0px"));
+        assert_eq!(default_offset("top"), ("0px", "10px"));
+        assert_eq!(default_offset("right"), ("10px", "0px"));
+        assert_eq!(default_offset("bottomleft"), ("10px", "10px"));
+    }
+}
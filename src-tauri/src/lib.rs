@@ -7,48 +7,98 @@ use tauri::{
 use std::sync::Mutex;
 use std::process::Child;
 
+mod aliases;
+mod audio;
 mod bridge;
+mod bundle;
+mod chain;
+mod clipboard;
 mod commands;
+mod completion;
 mod config;
+mod help;
+mod history;
+mod hotkeys;
 mod initial_setup;
+mod layout;
 mod log;
+mod monitors;
+mod plugins;
+mod registry;
 mod server_utils;
+mod settings;
+mod snippets;
+mod theme;
+mod undo;
 mod utils;
+mod writer;
 
 struct AppState {
     config: Mutex<config::BoxtsConfig>,
+    config_writer: config::ConfigWriter,
+    commands: registry::CommandRegistry,
+    chain: chain::ChainState,
+    history: Mutex<history::History>,
+    undo: Mutex<undo::UndoHistory>,
     dialog_active: Mutex<bool>,
+    window_drag: utils::WindowDrag,
+    hotkey_problems: Mutex<Vec<String>>,
+    // What /repeat says again
+    last_utterance: Mutex<Option<String>>,
+    // Whether the overlay is the multi-line editor, see /expand
+    expanded: Mutex<bool>,
     server_process: Mutex<Option<Child>>,
 }
 
-const AVAILABLE_COMMANDS: &[&str] = &["center", "exit", "nextmonitor", "topleft", "topright", "bottomleft", "bottomright", "resetconfig", "outputdevice", "volume", "clonevoice", "restartserver", "start", "listdevices", "stop", "changevoice", "ready", "lostfocus", "help"];
+#[tauri::command]
+fn get_available_commands(state: State<AppState>) -> Vec<String> {
+    let mut names = state.commands.names();
+    names.extend(aliases::alias_names(&state));
+    names
+}
+
+#[tauri::command]
+fn get_command_info(app: tauri::AppHandle, state: State<AppState>) -> Vec<registry::CommandInfo> {
+    state.commands.info(&app)
+}
+
+#[tauri::command]
+fn get_hotkeys(state: State<AppState>) -> Vec<help::HotkeyInfo> {
+    help::hotkeys(&state)
+}
+
+// None tells the frontend the command has no item selector
+#[tauri::command]
+fn get_command_items(command: String, app: tauri::AppHandle, state: State<AppState>) -> Option<Vec<String>> {
+    state.commands.find(&command).and_then(|cmd| cmd.items(&app))
+}
 
+// The items a multi-select item selector starts with ticked; None for single-select commands
 #[tauri::command]
-fn get_available_commands() -> Vec<String> {
-    AVAILABLE_COMMANDS.iter().map(|s| s.to_string()).collect()
+fn get_selected_items(command: String, app: tauri::AppHandle, state: State<AppState>) -> Option<Vec<String>> {
+    state.commands.find(&command).and_then(|cmd| cmd.selected_items(&app))
 }
 
 #[tauri::command]
 fn get_output_devices(state: State<AppState>) -> Vec<String> {
-    use cpal::traits::{HostTrait, DeviceTrait};
-    
-    let host = cpal::default_host();
-    let mut devices = match host.output_devices() {
-        Ok(devices) => {
-            devices
-                .filter_map(|device| device.name().ok())
-                .collect()
-        }
-        Err(_) => vec!["No output devices found".to_string()]
-    };
+    let mut devices = utils::list_output_devices();
     
     if devices.is_empty() {
         return vec!["No output devices found".to_string()];
     }
     
-    // Rotate list to put current output device first
-    let current_device = config::get_output_device(&state);
-    if let Some(current_index) = devices.iter().position(|d| d == &current_device) {
+    // The system default can be picked too, and selected devices that are unplugged stay
+    // listed so they can be unticked
+    let selected = config::get_output_devices(&state);
+    let extra = selected.iter().map(|device| device.name.clone()).chain(["Default".to_string()]);
+    for name in extra {
+        if !devices.contains(&name) {
+            devices.push(name);
+        }
+    }
+
+    // Rotate list to put the first selected output device first
+    if let Some(current_index) = selected.first().and_then(|first| devices.iter().position(|d| d == &first.name)) {
         devices.rotate_left(current_index);
     }
     
@@ -69,30 +119,7 @@ fn get_volume_values(state: State<AppState>) -> Vec<String> {
 
 #[tauri::command]
 fn get_voices(state: State<AppState>) -> Vec<String> {
-    use std::fs;
-    
-    let voices_path = if cfg!(debug_assertions) {
-        "../realtimetts-resources/voices"
-    } else {
-        "./realtimetts-resources/voices"
-    };
-    
-    let mut voices = match fs::read_dir(voices_path) {
-        Ok(entries) => {
-            entries
-                .filter_map(|entry| entry.ok())
-                .filter_map(|entry| {
-                    let path = entry.path();
-                    if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                        path.file_name().and_then(|s| s.to_str()).map(|s| s.to_string())
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<String>>()
-        }
-        Err(_) => vec!["No voices found".to_string()]
-    };
+    let mut voices = utils::list_voices();
     
     if voices.is_empty() {
         return vec!["No voices found".to_string()];
@@ -108,9 +135,40 @@ fn get_voices(state: State<AppState>) -> Vec<String> {
     voices
 }
 
+// Colors and sizes for the overlay; "theme-changed" carries the same when they change
+#[tauri::command]
+fn get_theme(state: State<AppState>) -> config::ThemeConfig {
+    config::get_theme(&state)
+}
+
+// Ctrl+E and Escape in the overlay; /expand does the same
+#[tauri::command]
+async fn set_expanded(expanded: bool, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    theme::set_expanded(app, state, expanded).await
+}
+
+// The multi-line editor's text, spoken as a single utterance
+#[tauri::command]
+async fn submit_expanded(text: String, state: State<'_, AppState>) -> Result<String, String> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Ok(String::new());
+    }
+    history::record(&state, &text);
+    handle_text(text, &state).await
+}
+
+// Shortcuts that failed to register at startup, shown once the overlay is up
+#[tauri::command]
+fn get_hotkey_problems(state: State<AppState>) -> Vec<String> {
+    state.hotkey_problems.lock().unwrap().clone()
+}
+
+// Alt+drag on the overlay; the position is saved once the window settles
 #[tauri::command]
-fn is_dialog_active(state: State<AppState>) -> bool {
-    *state.dialog_active.lock().unwrap()
+fn start_window_drag(window: tauri::WebviewWindow, app: tauri::AppHandle) -> Result<(), String> {
+    utils::begin_window_drag(&app);
+    window.start_dragging().map_err(|e| format!("Failed to drag window: {}", e))
 }
 
 #[tauri::command]
@@ -140,48 +198,144 @@ fn get_lostfocus_options(state: State<AppState>) -> Vec<String> {
     options
 }
 
+#[tauri::command]
+fn get_export_options() -> Vec<String> {
+    bundle::EXPORT_OPTIONS.iter().map(|s| s.to_string()).collect()
+}
+
+#[tauri::command]
+fn complete_input(text: String, cursor: usize, app: tauri::AppHandle, state: State<AppState>) -> completion::CompletionResult {
+    completion::complete(&app, &state, &text, cursor)
+}
+
+// Index 0 is the most recent line; None once the history runs out
+#[tauri::command]
+fn recall_history(kind: history::HistoryKind, index: usize, state: State<AppState>) -> Option<String> {
+    state.history.lock().unwrap().recall(kind, index)
+}
+
+#[tauri::command]
+fn search_history(query: String, kind: Option<history::HistoryKind>, skip: usize, state: State<AppState>) -> Option<String> {
+    state.history.lock().unwrap().search(&query, kind, skip)
+}
 
 #[tauri::command]
 async fn process_input(text: String, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    history::record(&state, &text);
+
     if text.starts_with('/') {
-        handle_command(&text[1..], app, state).await
+        handle_command(&text, app, state).await
     } else {
-        handle_text(text).await
+        handle_text(text, &state).await
+    }
+}
+
+// Splits the input at ';'; each step may itself be an alias for several steps
+fn expand_input(text: &str, state: &State<'_, AppState>) -> Result<Vec<String>, String> {
+    let mut steps = Vec::new();
+    for step in chain::split_input(text) {
+        let config = state.config.lock().unwrap();
+        steps.extend(aliases::expand(&config.aliases, &|name| state.commands.find(name).is_some(), &step)?);
+    }
+    Ok(steps)
+}
+
+async fn handle_command(text: &str, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    match expand_input(text, &state) {
+        Ok(steps) => run_expanded(&steps, app, state).await,
+        Err(e) => {
+            server_utils::emit_notification(app, e.clone()).await;
+            Err(e)
+        }
+    }
+}
+
+async fn run_expanded(steps: &[String], app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    match steps {
+        [] => Err("No command given".to_string()),
+        [step] => run_step(step, app, state).await,
+        _ => run_chain(steps, app, state).await,
+    }
+}
+
+async fn run_step(step: &str, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    match step.strip_prefix('/') {
+        Some(command_line) => run_command(command_line, app, state).await,
+        None => handle_text(step.to_string(), &state).await,
+    }
+}
+
+fn step_waits_for_ready(step: &str, state: &State<'_, AppState>) -> bool {
+    step.strip_prefix('/')
+        .and_then(|command_line| command_line.split(' ').next())
+        .and_then(|name| state.commands.find(name))
+        .is_some_and(|command| command.waits_for_ready())
+}
+
+// Runs steps until one fails; returns their results and the failure
+async fn run_steps(steps: &[String], app: tauri::AppHandle, state: State<'_, AppState>) -> (Vec<String>, Option<String>) {
+    let mut results = Vec::new();
+    for step in steps {
+        let ready_count = state.chain.ready_count();
+        match run_step(step, app.clone(), state.clone()).await {
+            Ok(result) => results.push(result),
+            Err(e) => return (results, Some(e)),
+        }
+
+        // Let the backend finish (e.g. loading a voice) before the next step talks to it
+        if step_waits_for_ready(step, &state) {
+            if let Err(e) = state.chain.wait_for_ready(ready_count).await {
+                return (results, Some(e));
+            }
+        }
+    }
+    (results, None)
+}
+
+async fn run_chain(steps: &[String], app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let run = state.chain.begin();
+    let (results, failure) = state.chain.scope(&run, run_steps(steps, app.clone(), state.clone())).await;
+
+    // Show what the steps reported as one notification, falling back to their results
+    let mut messages = state.chain.finish(run);
+    if messages.is_empty() {
+        messages = results;
+    }
+    if let Some(e) = &failure {
+        if messages.last() != Some(e) {
+            messages.push(e.clone());
+        }
+    }
+    let combined = messages.join(chain::RESULT_SEPARATOR);
+
+    server_utils::emit_notification(app.clone(), combined.clone()).await;
+    server_utils::emit_ready(app).await;
+
+    match failure {
+        Some(_) => Err(combined),
+        None => Ok(combined),
     }
 }
 
-async fn handle_command(command_str: &str, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+async fn run_command(command_str: &str, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
     let (command, argument) = if let Some(index) = command_str.find(' ') {
         (&command_str[..index], Some(command_str[index + 1..].to_string()))
     } else {
         (command_str, None)
     };
 
-    match command {
-        "center" => commands::center_command(app, state).await,
-        "exit" => commands::exit_command(app, state).await,
-        "nextmonitor" => commands::nextmonitor_command(app, state).await,
-        "topleft" => commands::topleft_command(app, state).await,
-        "topright" => commands::topright_command(app, state).await,
-        "bottomleft" => commands::bottomleft_command(app, state).await,
-        "bottomright" => commands::bottomright_command(app, state).await,
-        "resetconfig" => commands::resetconfig_command(app, state).await,
-        "outputdevice" => commands::outputdevice_command(argument, state).await,
-        "volume" => commands::volume_command(argument, state).await,
-        "clonevoice" => commands::clonevoice_command(app, state).await,
-        "restartserver" => commands::restartserver_command(state).await,
-        "start" => commands::start_command(argument, state).await,
-        "listdevices" => commands::listdevices_command().await,
-        "stop" => commands::stop_command().await,
-        "changevoice" => commands::changevoice_command(argument, state).await,
-        "ready" => commands::ready_command().await,
-        "lostfocus" => commands::lostfocus_command(argument, state).await,
-        "help" => commands::help_command(app).await,
-        _ => Err(format!("Unknown command: {}", command))
+    match state.commands.find(command) {
+        Some(cmd) => {
+            // Whatever the command changes in the config becomes a single undo step
+            undo::record_command(&state, cmd.run(app, argument)).await
+        },
+        None => Err(format!("Unknown command: {}", command))
     }
 }
 
-async fn handle_text(text: String) -> Result<String, String> {
+// Everything that gets spoken goes through here, so /repeat can say it again
+async fn handle_text(text: String, state: &State<'_, AppState>) -> Result<String, String> {
+    *state.last_utterance.lock().unwrap() = Some(text.clone());
     match bridge::send_speak_request(text).await {
         Ok(response) => Ok(response),
         Err(e) => Err(format!("Failed to send text to TTS: {}", e)),
@@ -199,15 +353,36 @@ pub fn run() {
         }
     }
     
+    let initial_config = config::load_config().unwrap_or_default();
+
     tauri::Builder::default()
-        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_global_shortcut::Builder::new()
+            .with_handler(|app, shortcut, event| hotkeys::handle_shortcut(app, shortcut, event))
+            .build())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .manage(AppState {
-            config: Mutex::new(config::load_config().unwrap_or_default()),
+            undo: Mutex::new(undo::UndoHistory::new()),
+            config: Mutex::new(initial_config),
+            config_writer: config::spawn_writer(),
+            commands: registry::CommandRegistry::new(),
+            chain: chain::ChainState::new(),
+            history: Mutex::new(history::History::load()),
             dialog_active: Mutex::new(false),
+            window_drag: utils::WindowDrag::default(),
+            hotkey_problems: Mutex::new(Vec::new()),
+            last_utterance: Mutex::new(None),
+            expanded: Mutex::new(false),
             server_process: Mutex::new(None),
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Moved(_) = event {
+                if window.label() == "main" {
+                    utils::window_moved(window.app_handle());
+                }
+            }
+        })
         .setup(|app| {
             let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
             let menu = Menu::with_items(app, &[&quit])?;
@@ -218,6 +393,8 @@ pub fn run() {
                 .on_menu_event(|app, event| {
                     if event.id() == "quit" {
                         let state = app.state::<AppState>();
+                        tauri::async_runtime::block_on(config::flush_config(&state));
+                        tauri::async_runtime::block_on(history::flush_history(&state));
                         server_utils::stop_server(state);
                         app.exit(0);
                     }
@@ -233,6 +410,18 @@ pub fn run() {
                 }
             });
 
+            // Global hotkeys live in the config, so they can be rebound with /hotkey
+            hotkeys::register_all(app.handle());
+
+            // Follow monitors being plugged in, unplugged or rearranged
+            monitors::watch_monitors(app.handle().clone());
+
+            // Fall back to the default output device while the chosen one is unplugged
+            audio::watch_output_devices(app.handle().clone());
+
+            // Speaks newly copied text while /clipboard watch is on
+            clipboard::watch_clipboard(app.handle().clone());
+
             // Start server after window is ready
             if let Some(_window) = app.get_webview_window("main") {
                 let app_handle = app.handle().clone();
@@ -259,7 +448,7 @@ pub fn run() {
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![process_input, get_available_commands, get_output_devices, get_volume_values, get_voices, is_dialog_active, get_environment_type, get_lostfocus_behaviour, get_lostfocus_options])
+        .invoke_handler(tauri::generate_handler![process_input, get_available_commands, get_output_devices, get_volume_values, get_voices, get_environment_type, get_lostfocus_behaviour, get_lostfocus_options, complete_input, get_export_options, get_command_info, get_hotkeys, get_command_items, get_selected_items, recall_history, search_history, start_window_drag, get_hotkey_problems, get_theme, set_expanded, submit_expanded])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
@@ -0,0 +1,262 @@
+use std::time::Duration;
+use tauri::{Manager, Monitor, PhysicalPosition, Position, State};
+use crate::{AppState, config, utils};
+use crate::config::MonitorIdentity;
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+// Which monitor the overlay goes on, and whether it is the one saved in config
+#[derive(Debug, PartialEq)]
+enum Resolved {
+    Saved(usize),
+    Fallback(usize),
+}
+
+pub fn identity(monitor: &Monitor) -> MonitorIdentity {
+    MonitorIdentity {
+        name: monitor.name().cloned().unwrap_or_default(),
+        x: monitor.position().x,
+        y: monitor.position().y,
+        width: monitor.size().width,
+        height: monitor.size().height,
+    }
+}
+
+fn same_geometry(a: &MonitorIdentity, b: &MonitorIdentity) -> bool {
+    a.x == b.x && a.y == b.y && a.width == b.width && a.height == b.height
+}
+
+// Same name and geometry, then same name (resolution or arrangement changed) count as the saved
+// monitor. Same geometry under another name, the saved index, the primary monitor and the first
+// one are only stand-ins until it comes back.
+fn resolve(monitors: &[MonitorIdentity], saved: Option<&MonitorIdentity>, saved_index: u32, primary: Option<usize>) -> Option<Resolved> {
+    if monitors.is_empty() {
+        return None;
+    }
+
+    let saved = match saved {
+        Some(saved) => saved,
+        // Configs from before monitors were identified only have the index
+        None => {
+            let index = saved_index as usize;
+            return Some(if index < monitors.len() {
+                Resolved::Saved(index)
+            } else {
+                Resolved::Fallback(primary.unwrap_or(0))
+            });
+        }
+    };
+
+    if let Some(index) = monitors.iter().position(|m| m == saved) {
+        return Some(Resolved::Saved(index));
+    }
+    if !saved.name.is_empty() {
+        if let Some(index) = monitors.iter().position(|m| m.name == saved.name) {
+            return Some(Resolved::Saved(index));
+        }
+    }
+
+    let fallback = monitors
+        .iter()
+        .position(|m| same_geometry(m, saved))
+        .or_else(|| Some(saved_index as usize).filter(|index| *index < monitors.len()))
+        .or(primary)
+        .unwrap_or(0);
+    Some(Resolved::Fallback(fallback))
+}
+
+fn connected(app: &tauri::AppHandle) -> Result<Vec<Monitor>, String> {
+    app.available_monitors()
+        .map_err(|e| format!("Failed to get available monitors: {}", e))
+}
+
+fn primary_index(app: &tauri::AppHandle, monitors: &[Monitor]) -> Option<usize> {
+    let primary = identity(&app.primary_monitor().ok()??);
+    monitors.iter().position(|m| identity(m) == primary)
+}
+
+// Index of `monitor` among the connected ones, for the monitor_id fallback
+pub fn index_of(app: &tauri::AppHandle, monitor: &Monitor) -> Option<usize> {
+    let target = identity(monitor);
+    connected(app).ok()?.iter().position(|m| identity(m) == target)
+}
+
+// Puts the window inside the monitor so the anchor is applied there
+fn move_to_monitor(app: &tauri::AppHandle, monitor: &Monitor) -> Result<(), String> {
+    let window = app.get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+
+    let work_area = monitor.work_area();
+    let center_pos = PhysicalPosition::new(
+        work_area.position.x + work_area.size.width as i32 / 2,
+        work_area.position.y + work_area.size.height as i32 / 2
+    );
+
+    window.set_position(Position::Physical(center_pos))
+        .map_err(|e| format!("Failed to move to target monitor: {}", e))
+}
+
+fn display_name(monitor: &Monitor) -> &str {
+    monitor.name().map_or("Unknown", |name| name.as_str())
+}
+
+// Moves to the saved monitor, or a stand-in without forgetting the saved one, and re-applies the anchor
+pub async fn apply_saved_monitor(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let monitors = connected(&app)?;
+    let identities: Vec<MonitorIdentity> = monitors.iter().map(identity).collect();
+    let (saved, saved_index, position) = {
+        let config = state.config.lock().unwrap();
+        (config.window.monitor.clone(), config.window.monitor_id, config.window.position.clone())
+    };
+
+    let resolved = resolve(&identities, saved.as_ref(), saved_index, primary_index(&app, &monitors))
+        .ok_or("No monitors available")?;
+    let message = match resolved {
+        Resolved::Saved(index) => {
+            move_to_monitor(&app, &monitors[index])?;
+            format!("Switched to monitor: {}", display_name(&monitors[index]))
+        },
+        Resolved::Fallback(index) => {
+            move_to_monitor(&app, &monitors[index])?;
+            let missing = saved.map(|saved| saved.name).unwrap_or_else(|| format!("#{}", saved_index));
+            format!("Monitor {} is not connected, using {}", missing, display_name(&monitors[index]))
+        },
+    };
+
+    utils::apply_window_position(app, state, &position).await?;
+    Ok(message)
+}
+
+// Places the window on the monitor under the mouse; the saved monitor stays as it is
+pub async fn apply_cursor_monitor(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let cursor = app.cursor_position()
+        .map_err(|e| format!("Failed to get cursor position: {}", e))?;
+    let monitor = app.monitor_from_point(cursor.x, cursor.y)
+        .map_err(|e| format!("Failed to find monitor under cursor: {}", e))?
+        .ok_or("No monitor under the cursor")?;
+
+    move_to_monitor(&app, &monitor)?;
+    let position = config::get_window_position(&state);
+    utils::apply_window_position(app, state, &position).await?;
+    Ok(format!("Following cursor to monitor: {}", display_name(&monitor)))
+}
+
+// Makes `index` the saved monitor and places the window on it
+pub async fn switch_to_monitor(app: tauri::AppHandle, state: State<'_, AppState>, index: usize) -> Result<String, String> {
+    let monitors = connected(&app)?;
+    let monitor = monitors.get(index)
+        .ok_or_else(|| format!("No monitor with id {}", index))?;
+
+    move_to_monitor(&app, monitor)?;
+    config::set_monitor(&state, index as u32, identity(monitor)).map_err(|e| e.to_string())?;
+
+    let position = config::get_window_position(&state);
+    utils::apply_window_position(app, state, &position).await?;
+    Ok(format!("Switched to monitor: {}", display_name(monitor)))
+}
+
+pub async fn switch_to_next_monitor(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let monitors = connected(&app)?;
+    if monitors.len() <= 1 {
+        return Ok("Only one monitor available".to_string());
+    }
+
+    let window = app.get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    let current = window.current_monitor()
+        .map_err(|e| format!("Failed to get current monitor: {}", e))?
+        .ok_or("No current monitor detected")?;
+    let current_index = index_of(&app, &current).unwrap_or(0);
+
+    switch_to_monitor(app, state, (current_index + 1) % monitors.len()).await
+}
+
+// There is no hotplug event, so compare the monitor layout every few seconds
+pub fn watch_monitors(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let snapshot = |app: &tauri::AppHandle| -> Vec<MonitorIdentity> {
+            connected(app).map(|monitors| monitors.iter().map(identity).collect()).unwrap_or_default()
+        };
+        let mut last = snapshot(&app);
+
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            let current = snapshot(&app);
+            if current == last || current.is_empty() {
+                continue;
+            }
+            last = current;
+
+            crate::log::tauri_log(&format!("Monitor layout changed, {} connected", last.len()));
+            let state = app.state::<AppState>();
+            // With follow_cursor the saved monitor isn't used, so stay with the mouse instead
+            let applied = if config::get_follow_cursor(&state) {
+                apply_cursor_monitor(app.clone(), state).await
+            } else {
+                apply_saved_monitor(app.clone(), state).await
+            };
+            match applied {
+                Ok(message) => crate::log::tauri_log(&message),
+                Err(e) => crate::log::tauri_log(&format!("Failed to re-apply window placement: {}", e)),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, width: u32) -> MonitorIdentity {
+        MonitorIdentity { name: name.to_string(), x, y: 0, width, height: 1080 }
+    }
+
+    fn desk() -> Vec<MonitorIdentity> {
+        vec![monitor("DELL U2720Q", 0, 1920), monitor("LG HDR 4K", 1920, 2560)]
+    }
+
+    #[test]
+    fn no_monitors_resolve_to_nothing() {
+        assert_eq!(resolve(&[], Some(&monitor("LG HDR 4K", 1920, 2560)), 1, None), None);
+    }
+
+    #[test]
+    fn finds_the_saved_monitor_by_name_and_geometry() {
+        let saved = monitor("LG HDR 4K", 1920, 2560);
+        assert_eq!(resolve(&desk(), Some(&saved), 0, Some(0)), Some(Resolved::Saved(1)));
+    }
+
+    #[test]
+    fn finds_the_saved_monitor_by_name_after_rearranging() {
+        let saved = monitor("LG HDR 4K", -2560, 3840);
+        assert_eq!(resolve(&desk(), Some(&saved), 0, Some(0)), Some(Resolved::Saved(1)));
+    }
+
+    #[test]
+    fn same_geometry_under_another_name_is_only_a_stand_in() {
+        let saved = monitor("Old Monitor", 1920, 2560);
+        assert_eq!(resolve(&desk(), Some(&saved), 0, Some(0)), Some(Resolved::Fallback(1)));
+    }
+
+    #[test]
+    fn unplugged_monitor_falls_back_to_index_then_primary_then_first() {
+        let saved = monitor("Projector", 5000, 1280);
+        assert_eq!(resolve(&desk(), Some(&saved), 1, Some(0)), Some(Resolved::Fallback(1)));
+        assert_eq!(resolve(&desk(), Some(&saved), 7, Some(1)), Some(Resolved::Fallback(1)));
+        assert_eq!(resolve(&desk(), Some(&saved), 7, None), Some(Resolved::Fallback(0)));
+    }
+
+    #[test]
+    fn unnamed_monitors_do_not_match_each_other_by_name() {
+        let monitors = vec![monitor("", 0, 1920), monitor("", 1920, 2560)];
+        let saved = monitor("", 5000, 1280);
+        assert_eq!(resolve(&monitors, Some(&saved), 7, Some(1)), Some(Resolved::Fallback(1)));
+    }
+
+    #[test]
+    fn configs_without_an_identity_use_the_saved_index() {
+        assert_eq!(resolve(&desk(), None, 1, Some(0)), Some(Resolved::Saved(1)));
+        assert_eq!(resolve(&desk(), None, 4, Some(1)), Some(Resolved::Fallback(1)));
+        assert_eq!(resolve(&desk(), None, 4, None), Some(Resolved::Fallback(0)));
+    }
+}
@@ -0,0 +1,348 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::io::AsyncWriteExt;
+use crate::{AppState, config, server_utils};
+use crate::registry::{Argument, Category, Command, CommandFuture};
+
+const MANIFEST_FILE: &str = "plugin.json";
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+// plugins/<folder>/plugin.json; `executable` is relative to that folder
+#[derive(Deserialize, Clone)]
+pub struct PluginManifest {
+    pub name: String,
+    pub description: String,
+    pub executable: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub argument: Option<String>,
+    #[serde(default)]
+    pub items: Vec<String>,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+// Written to the plugin's stdin as a single JSON object
+#[derive(Serialize)]
+struct PluginRequest<'a> {
+    command: &'a str,
+    argument: Option<&'a str>,
+    voice: String,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum PluginAction {
+    Speak { text: String },
+    Notify { message: String },
+    Command { command: String },
+}
+
+#[derive(Deserialize)]
+struct PluginResponse {
+    #[serde(default)]
+    actions: Vec<PluginAction>,
+}
+
+#[derive(Clone)]
+pub struct PluginCommand {
+    manifest: PluginManifest,
+    directory: PathBuf,
+    executable: PathBuf,
+}
+
+pub fn get_plugins_path() -> PathBuf {
+    if cfg!(debug_assertions) {
+        PathBuf::from("../plugins")
+    } else {
+        PathBuf::from("./plugins")
+    }
+}
+
+fn parse_manifest(contents: &str) -> Result<PluginManifest, String> {
+    let manifest: PluginManifest = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+
+    if manifest.name.is_empty() || !manifest.name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("invalid name {:?}", manifest.name));
+    }
+    Ok(manifest)
+}
+
+// The executable has to be a file inside the plugin's folder; "..", absolute paths and links
+// pointing elsewhere are refused
+fn resolve_executable(directory: &Path, executable: &str) -> Result<PathBuf, String> {
+    let folder = directory.canonicalize().map_err(|e| e.to_string())?;
+    let path = folder
+        .join(executable)
+        .canonicalize()
+        .map_err(|e| format!("executable {}: {}", executable, e))?;
+    if !path.starts_with(&folder) || !path.is_file() {
+        return Err(format!("executable {} is not a file in the plugin folder", executable));
+    }
+    Ok(path)
+}
+
+fn read_manifest(directory: &Path) -> Result<(PluginManifest, PathBuf), String> {
+    let contents = fs::read_to_string(directory.join(MANIFEST_FILE)).map_err(|e| e.to_string())?;
+    let manifest = parse_manifest(&contents)?;
+    let executable = resolve_executable(directory, &manifest.executable)?;
+    Ok((manifest, executable))
+}
+
+// Every folder with a readable manifest; broken ones are logged and skipped
+pub fn load_plugins() -> Vec<PluginCommand> {
+    let entries = match fs::read_dir(get_plugins_path()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut plugins = Vec::new();
+    for entry in entries.flatten() {
+        let directory = entry.path();
+        if !directory.join(MANIFEST_FILE).is_file() {
+            continue;
+        }
+        match read_manifest(&directory) {
+            Ok((manifest, executable)) => {
+                crate::log::tauri_log(&format!("Loaded plugin /{} from {}", manifest.name, directory.display()));
+                plugins.push(PluginCommand { manifest, directory, executable });
+            },
+            Err(e) => crate::log::tauri_log(&format!("Skipping plugin in {}: {}", directory.display(), e)),
+        }
+    }
+    plugins.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    plugins
+}
+
+async fn execute(plugin: &PluginCommand, request: &[u8]) -> Result<PluginResponse, String> {
+    let name = &plugin.manifest.name;
+    let mut child = tokio::process::Command::new(&plugin.executable)
+        .args(&plugin.manifest.args)
+        .current_dir(&plugin.directory)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start plugin {}: {}", name, e))?;
+
+    // Writing the request counts towards the timeout too, a plugin that never reads would block it.
+    // Dropping the child on timeout kills it.
+    let stdin = child.stdin.take();
+    let run = async move {
+        if let Some(mut stdin) = stdin {
+            // A plugin that doesn't need the request may exit without reading it
+            match stdin.write_all(request).await {
+                Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+                    return Err(format!("Failed to write to plugin {}: {}", name, e));
+                },
+                _ => {},
+            }
+        }
+        child.wait_with_output().await.map_err(|e| format!("Plugin {} failed: {}", name, e))
+    };
+
+    let timeout = Duration::from_secs(plugin.manifest.timeout_secs);
+    let output = match tokio::time::timeout(timeout, run).await {
+        Ok(output) => output?,
+        Err(_) => return Err(format!("Plugin {} timed out after {}s", name, plugin.manifest.timeout_secs)),
+    };
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in stderr.lines().filter(|line| !line.trim().is_empty()) {
+        crate::log::tauri_log(&format!("[{}] {}", name, line));
+    }
+
+    if !output.status.success() {
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+            .unwrap_or_else(|| output.status.to_string());
+        return Err(format!("Plugin {} failed: {}", name, reason));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Plugin {} sent an invalid reply: {}", name, e))
+}
+
+async fn perform(app: tauri::AppHandle, name: &str, actions: Vec<PluginAction>) -> Result<String, String> {
+    let mut result = format!("Plugin {} done", name);
+    for action in actions {
+        match action {
+            PluginAction::Speak { text } => {
+                // Like typed text, so /repeat says it again
+                crate::handle_text(text, &app.state::<AppState>()).await?;
+            },
+            PluginAction::Notify { message } => {
+                server_utils::emit_notification(app.clone(), message.clone()).await;
+                result = message;
+            },
+            PluginAction::Command { command } => {
+                let state = app.state::<AppState>();
+                let line = format!("/{}", command.trim().trim_start_matches('/'));
+                let steps = crate::expand_input(&line, &state)?;
+
+                // Plugins calling plugins could loop forever, also through an alias
+                for step in &steps {
+                    let target = step
+                        .strip_prefix('/')
+                        .and_then(|command_line| command_line.split(' ').next())
+                        .unwrap_or_default();
+                    match state.commands.find(target) {
+                        Some(found) if matches!(found.category(), Category::Plugins) => {
+                            return Err(format!("Plugin {} cannot run another plugin (/{})", name, target));
+                        },
+                        _ => {},
+                    }
+                }
+                result = crate::run_expanded(&steps, app.clone(), state).await?;
+            },
+        }
+    }
+    Ok(result)
+}
+
+async fn run_plugin(plugin: PluginCommand, app: tauri::AppHandle, argument: Option<String>) -> Result<String, String> {
+    let voice = config::get_voice(&app.state()).unwrap_or_default();
+    let request = PluginRequest {
+        command: &plugin.manifest.name,
+        argument: argument.as_deref(),
+        voice: config::voice_settings_key(&voice),
+    };
+    let request = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
+
+    let result = match execute(&plugin, &request).await {
+        Ok(response) => perform(app.clone(), &plugin.manifest.name, response.actions).await,
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = &result {
+        crate::log::tauri_log(e);
+        server_utils::emit_notification(app, e.clone()).await;
+    }
+    result
+}
+
+impl Command for PluginCommand {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn description(&self) -> &str {
+        &self.manifest.description
+    }
+
+    fn category(&self) -> Category {
+        Category::Plugins
+    }
+
+    fn argument(&self) -> Argument<'_> {
+        match &self.manifest.argument {
+            Some(argument) => Argument::Optional(argument),
+            None => Argument::None,
+        }
+    }
+
+    fn has_items(&self) -> bool {
+        !self.manifest.items.is_empty()
+    }
+
+    fn items(&self, _app: &tauri::AppHandle) -> Option<Vec<String>> {
+        if self.manifest.items.is_empty() {
+            None
+        } else {
+            Some(self.manifest.items.clone())
+        }
+    }
+
+    fn run(&self, app: tauri::AppHandle, argument: Option<String>) -> CommandFuture {
+        Box::pin(run_plugin(self.clone(), app, argument))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh, empty folder per test, so tests running in parallel don't see each other's files
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("boxts-plugin-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_a_minimal_manifest_with_defaults() {
+        let manifest = parse_manifest(r#"{"name": "weather", "description": "Forecast", "executable": "weather.exe"}"#).unwrap();
+        assert_eq!(manifest.name, "weather");
+        assert!(manifest.args.is_empty());
+        assert!(manifest.argument.is_none());
+        assert!(manifest.items.is_empty());
+        assert_eq!(manifest.timeout_secs, DEFAULT_TIMEOUT_SECS);
+    }
+
+    #[test]
+    fn rejects_bad_names_and_missing_fields() {
+        for name in ["", "two words", "../up", "semi;colon"] {
+            let contents = format!(r#"{{"name": {:?}, "description": "", "executable": "run"}}"#, name);
+            assert!(parse_manifest(&contents).is_err(), "{:?} was accepted", name);
+        }
+        assert!(parse_manifest(r#"{"name": "weather", "description": "Forecast"}"#).is_err());
+        assert!(parse_manifest("not json").is_err());
+    }
+
+    #[test]
+    fn reads_every_action_type() {
+        let response: PluginResponse = serde_json::from_str(
+            r#"{"actions": [
+                {"type": "speak", "text": "Rain all day"},
+                {"type": "notify", "message": "forecast read"},
+                {"type": "command", "command": "/volume 0.5"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(matches!(&response.actions[0], PluginAction::Speak { text } if text == "Rain all day"));
+        assert!(matches!(&response.actions[1], PluginAction::Notify { message } if message == "forecast read"));
+        assert!(matches!(&response.actions[2], PluginAction::Command { command } if command == "/volume 0.5"));
+    }
+
+    #[test]
+    fn replies_without_actions_do_nothing() {
+        let response: PluginResponse = serde_json::from_str("{}").unwrap();
+        assert!(response.actions.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_actions() {
+        assert!(serde_json::from_str::<PluginResponse>(r#"{"actions": [{"type": "shell", "command": "rm"}]}"#).is_err());
+        assert!(serde_json::from_str::<PluginResponse>(r#"{"actions": [{"type": "speak"}]}"#).is_err());
+    }
+
+    #[test]
+    fn executables_must_stay_in_the_plugin_folder() {
+        let root = scratch_dir("executable");
+        let folder = root.join("weather");
+        fs::create_dir_all(folder.join("bin")).unwrap();
+        fs::write(folder.join("bin").join("run"), b"").unwrap();
+        fs::write(root.join("outside"), b"").unwrap();
+
+        assert!(resolve_executable(&folder, "bin/run").is_ok());
+        assert!(resolve_executable(&folder, "../outside").is_err());
+        assert!(resolve_executable(&folder, &root.join("outside").to_string_lossy()).is_err());
+        assert!(resolve_executable(&folder, "missing").is_err());
+        assert!(resolve_executable(&folder, "bin").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,628 @@
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use tauri::Manager;
+use crate::{AppState, aliases, audio, clipboard, commands, config, help, hotkeys, plugins, settings, snippets, theme};
+
+pub type CommandFuture = Pin<Box<dyn Future<Output = Result<String, String>> + Send>>;
+type CommandHandler = fn(tauri::AppHandle, Option<String>) -> CommandFuture;
+type ItemProvider = fn(&tauri::AppHandle) -> Vec<String>;
+type ArgumentCompleter = fn(&tauri::AppHandle, &str) -> ArgumentCompletion;
+type CurrentValue = fn(&tauri::AppHandle) -> String;
+
+// Values for the argument word being typed; `offset` is the byte index in the argument where that word starts
+#[derive(Default)]
+pub struct ArgumentCompletion {
+    pub offset: usize,
+    pub values: Vec<String>,
+}
+
+#[derive(Clone, Copy)]
+pub enum Category {
+    Window,
+    Audio,
+    Voice,
+    Settings,
+    System,
+    Plugins,
+}
+
+impl Category {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Window => "Window Positioning",
+            Category::Audio => "Audio Configuration",
+            Category::Voice => "Voice Management",
+            Category::Settings => "Application Settings",
+            Category::System => "System Commands",
+            Category::Plugins => "Plugin Commands",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Argument<'a> {
+    None,
+    Required(&'a str),
+    Optional(&'a str),
+}
+
+pub trait Command: Send + Sync {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    fn category(&self) -> Category;
+
+    fn aliases(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    fn argument(&self) -> Argument<'_> {
+        Argument::None
+    }
+
+    fn examples(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    // The setting this command changes, as it is right now
+    fn current_value(&self, _app: &tauri::AppHandle) -> Option<String> {
+        None
+    }
+
+    fn has_items(&self) -> bool {
+        false
+    }
+
+    // Options for the item selector; None means the command takes free text or nothing
+    fn items(&self, _app: &tauri::AppHandle) -> Option<Vec<String>> {
+        None
+    }
+
+    // The options ticked right now when several can be picked at once; None means pick one
+    fn selected_items(&self, _app: &tauri::AppHandle) -> Option<Vec<String>> {
+        None
+    }
+
+    // By default the item selector options double as argument completions
+    fn complete_argument(&self, app: &tauri::AppHandle, _argument: &str) -> ArgumentCompletion {
+        ArgumentCompletion {
+            offset: 0,
+            values: self.items(app).unwrap_or_default(),
+        }
+    }
+
+    // Whether the frontend should show loading until the backend sends its ready signal
+    fn waits_for_ready(&self) -> bool {
+        false
+    }
+
+    fn run(&self, app: tauri::AppHandle, argument: Option<String>) -> CommandFuture;
+}
+
+pub struct BuiltinCommand {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    category: Category,
+    argument: Argument<'static>,
+    examples: &'static [&'static str],
+    current: Option<CurrentValue>,
+    items: Option<ItemProvider>,
+    selected: Option<ItemProvider>,
+    completer: Option<ArgumentCompleter>,
+    waits_for_ready: bool,
+    handler: CommandHandler,
+}
+
+impl BuiltinCommand {
+    fn new(name: &'static str, category: Category, description: &'static str, handler: CommandHandler) -> Self {
+        Self {
+            name,
+            aliases: &[],
+            description,
+            category,
+            argument: Argument::None,
+            examples: &[],
+            current: None,
+            items: None,
+            selected: None,
+            completer: None,
+            waits_for_ready: false,
+            handler,
+        }
+    }
+
+    fn with_aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    fn with_argument(mut self, argument: Argument<'static>) -> Self {
+        self.argument = argument;
+        self
+    }
+
+    fn with_examples(mut self, examples: &'static [&'static str]) -> Self {
+        self.examples = examples;
+        self
+    }
+
+    fn with_current(mut self, current: CurrentValue) -> Self {
+        self.current = Some(current);
+        self
+    }
+
+    fn with_items(mut self, items: ItemProvider) -> Self {
+        self.items = Some(items);
+        self
+    }
+
+    // The picks are passed joined by audio::DEVICE_SEPARATOR
+    fn with_multi_select(mut self, selected: ItemProvider) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    fn with_completer(mut self, completer: ArgumentCompleter) -> Self {
+        self.completer = Some(completer);
+        self
+    }
+
+    fn waiting_for_ready(mut self) -> Self {
+        self.waits_for_ready = true;
+        self
+    }
+}
+
+impl Command for BuiltinCommand {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn category(&self) -> Category {
+        self.category
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        self.aliases.to_vec()
+    }
+
+    fn argument(&self) -> Argument<'_> {
+        self.argument
+    }
+
+    fn examples(&self) -> Vec<&str> {
+        self.examples.to_vec()
+    }
+
+    fn current_value(&self, app: &tauri::AppHandle) -> Option<String> {
+        self.current.map(|current| current(app))
+    }
+
+    fn has_items(&self) -> bool {
+        self.items.is_some()
+    }
+
+    fn items(&self, app: &tauri::AppHandle) -> Option<Vec<String>> {
+        self.items.map(|provider| provider(app))
+    }
+
+    fn selected_items(&self, app: &tauri::AppHandle) -> Option<Vec<String>> {
+        self.selected.map(|provider| provider(app))
+    }
+
+    fn complete_argument(&self, app: &tauri::AppHandle, argument: &str) -> ArgumentCompletion {
+        match self.completer {
+            Some(completer) => completer(app, argument),
+            None => ArgumentCompletion {
+                offset: 0,
+                values: self.items(app).unwrap_or_default(),
+            },
+        }
+    }
+
+    fn waits_for_ready(&self) -> bool {
+        self.waits_for_ready
+    }
+
+    fn run(&self, app: tauri::AppHandle, argument: Option<String>) -> CommandFuture {
+        (self.handler)(app, argument)
+    }
+}
+
+// What the frontend needs to know about a command for completion, loading state and help
+#[derive(Serialize)]
+pub struct CommandInfo {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub description: String,
+    pub category: String,
+    pub usage: String,
+    pub examples: Vec<String>,
+    pub current_value: Option<String>,
+    pub has_items: bool,
+    pub multi_select: bool,
+    pub waits_for_ready: bool,
+}
+
+pub fn usage(command: &dyn Command) -> String {
+    match command.argument() {
+        Argument::None => format!("/{}", command.name()),
+        Argument::Required(name) => format!("/{} <{}>", command.name(), name),
+        Argument::Optional(name) => format!("/{} [{}]", command.name(), name),
+    }
+}
+
+pub struct CommandRegistry {
+    commands: Vec<Box<dyn Command>>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        let plugins = plugins::load_plugins()
+            .into_iter()
+            .map(|plugin| Box::new(plugin) as Box<dyn Command>)
+            .collect();
+        Self::with_plugins(builtin_commands(), plugins)
+    }
+
+    // Plugins come after the builtins and never take a name or alias that is already used
+    fn with_plugins(builtins: Vec<Box<dyn Command>>, plugins: Vec<Box<dyn Command>>) -> Self {
+        let mut registry = Self { commands: builtins };
+        for plugin in plugins {
+            if registry.find(plugin.name()).is_some() {
+                crate::log::tauri_log(&format!("Skipping plugin /{}: the name is already taken", plugin.name()));
+                continue;
+            }
+            registry.commands.push(plugin);
+        }
+        registry
+    }
+
+    pub fn find(&self, name: &str) -> Option<&dyn Command> {
+        self.commands
+            .iter()
+            .find(|command| command.name() == name || command.aliases().contains(&name))
+            .map(|command| command.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Command> {
+        self.commands.iter().map(|command| command.as_ref())
+    }
+
+    // Names and aliases, everything the user can type after the slash
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for command in self.iter() {
+            names.push(command.name().to_string());
+            names.extend(command.aliases().iter().map(|alias| alias.to_string()));
+        }
+        names
+    }
+
+    pub fn info(&self, app: &tauri::AppHandle) -> Vec<CommandInfo> {
+        self.iter()
+            .map(|command| command_info(command, command.current_value(app), command.selected_items(app).is_some()))
+            .collect()
+    }
+}
+
+// The parts that need the app, the current value and whether several items can be picked, are passed in
+fn command_info(command: &dyn Command, current_value: Option<String>, multi_select: bool) -> CommandInfo {
+    CommandInfo {
+        name: command.name().to_string(),
+        aliases: command.aliases().iter().map(|alias| alias.to_string()).collect(),
+        description: command.description().to_string(),
+        category: command.category().title().to_string(),
+        usage: usage(command),
+        examples: command.examples().iter().map(|example| example.to_string()).collect(),
+        current_value,
+        has_items: command.has_items(),
+        multi_select,
+        waits_for_ready: command.waits_for_ready(),
+    }
+}
+
+fn builtin_commands() -> Vec<Box<dyn Command>> {
+    let builtins = vec![
+        // Window positioning
+        BuiltinCommand::new("center", Category::Window, "Center window on current monitor",
+            |app, _| Box::pin(async move { commands::center_command(app.clone(), app.state()).await }))
+            .with_current(|app| config::get_window_position(&app.state())),
+        BuiltinCommand::new("topleft", Category::Window, "Move window to top-left corner",
+            |app, _| Box::pin(async move { commands::topleft_command(app.clone(), app.state()).await }))
+            .with_current(|app| config::get_window_position(&app.state())),
+        BuiltinCommand::new("topright", Category::Window, "Move window to top-right corner",
+            |app, _| Box::pin(async move { commands::topright_command(app.clone(), app.state()).await }))
+            .with_current(|app| config::get_window_position(&app.state())),
+        BuiltinCommand::new("bottomleft", Category::Window, "Move window to bottom-left corner",
+            |app, _| Box::pin(async move { commands::bottomleft_command(app.clone(), app.state()).await }))
+            .with_current(|app| config::get_window_position(&app.state())),
+        BuiltinCommand::new("bottomright", Category::Window, "Move window to bottom-right corner",
+            |app, _| Box::pin(async move { commands::bottomright_command(app.clone(), app.state()).await }))
+            .with_current(|app| config::get_window_position(&app.state())),
+        BuiltinCommand::new("position", Category::Window, "Offset the window from its anchor, in pixels or percent of the screen",
+            |app, argument| Box::pin(async move { commands::position_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Required("x y"))
+            .with_examples(&["/position 5% 40px", "/position 0 -120px"])
+            .with_current(|app| {
+                let (offset_x, offset_y) = config::get_window_offset(&app.state());
+                format!("{} {} from {}", offset_x, offset_y, config::get_window_position(&app.state()))
+            }),
+        BuiltinCommand::new("followcursor", Category::Window, "Show the overlay on the monitor under the mouse (toggles without an argument)",
+            |app, argument| Box::pin(async move { commands::followcursor_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Optional("on|off"))
+            .with_completer(|_, _| ArgumentCompletion { offset: 0, values: vec!["on".to_string(), "off".to_string()] })
+            .with_examples(&["/followcursor", "/followcursor off"])
+            .with_current(|app| if config::get_follow_cursor(&app.state()) { "on" } else { "off" }.to_string()),
+        BuiltinCommand::new("expand", Category::Window, "Grow the overlay into a multi-line editor, or back (Ctrl+E in the overlay)",
+            |app, argument| Box::pin(async move { commands::expand_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Optional("on|off"))
+            .with_completer(|_, _| ArgumentCompletion { offset: 0, values: vec!["on".to_string(), "off".to_string()] })
+            .with_examples(&["/expand"]),
+        BuiltinCommand::new("nextmonitor", Category::Window, "Switch to next available monitor",
+            |app, _| Box::pin(async move { commands::nextmonitor_command(app.clone(), app.state()).await }))
+            .with_current(|app| {
+                let window = app.state::<AppState>().config.lock().unwrap().window.clone();
+                window.monitor.map(|monitor| monitor.name).unwrap_or_else(|| window.monitor_id.to_string())
+            }),
+
+        // Audio configuration
+        BuiltinCommand::new("outputdevice", Category::Audio, "Select the audio output devices; every utterance plays on all of them (space ticks one in the selector)",
+            |app, argument| Box::pin(async move { commands::outputdevice_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Required("device | device"))
+            .with_items(|app| crate::get_output_devices(app.state()))
+            .with_multi_select(|app| audio::selected_device_names(&app.state()))
+            .waiting_for_ready()
+            .with_examples(&["/outputdevice Headset", "/outputdevice Headset | CABLE Input (VB-Audio Virtual Cable)"])
+            .with_current(|app| audio::selected_device_names(&app.state()).join(audio::DEVICE_SEPARATOR)),
+        BuiltinCommand::new("outputvolume", Category::Audio, "Set the volume of one output device (0.00-2.00), on top of /volume",
+            |app, argument| Box::pin(async move { commands::outputvolume_command(argument, app.state()).await }))
+            .with_argument(Argument::Required("volume [device]"))
+            .with_completer(audio::complete_output_volume)
+            .with_examples(&["/outputvolume 0.6 CABLE Input (VB-Audio Virtual Cable)", "/outputvolume 1.2"])
+            .with_current(|app| {
+                config::get_output_devices(&app.state())
+                    .iter()
+                    .map(|device| format!("{} {:.2}", device.name, device.volume))
+                    .collect::<Vec<String>>()
+                    .join(audio::DEVICE_SEPARATOR)
+            }),
+        BuiltinCommand::new("volume", Category::Audio, "Set TTS volume (0.00-1.00)",
+            |app, argument| Box::pin(async move { commands::volume_command(argument, app.state()).await }))
+            .with_argument(Argument::Required("volume"))
+            .with_items(|app| crate::get_volume_values(app.state()))
+            .with_examples(&["/volume 0.3"])
+            .with_current(|app| format!("{:.2}", config::get_volume(&app.state()))),
+        BuiltinCommand::new("listdevices", Category::Audio, "List available audio devices in console",
+            |_, _| Box::pin(commands::listdevices_command())),
+        BuiltinCommand::new("mute", Category::Audio, "Mute or unmute speech without touching the volume (toggles without an argument)",
+            |app, argument| Box::pin(async move { commands::mute_command(argument, app.clone()).await }))
+            .with_argument(Argument::Optional("on|off"))
+            .with_completer(|_, _| ArgumentCompletion { offset: 0, values: vec!["on".to_string(), "off".to_string()] })
+            .with_examples(&["/mute", "/mute off"]),
+
+        // Voice management
+        BuiltinCommand::new("start", Category::Voice, "Start TTS with voice selection",
+            |app, argument| Box::pin(async move { commands::start_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Required("voice"))
+            .with_items(|app| crate::get_voices(app.state()))
+            .waiting_for_ready()
+            .with_examples(&["/start bob.json"])
+            .with_current(|app| config::get_voice(&app.state()).unwrap_or_default()),
+        BuiltinCommand::new("stop", Category::Voice, "Stop TTS and clean up resources",
+            |_, _| Box::pin(commands::stop_command()))
+            .waiting_for_ready(),
+        BuiltinCommand::new("changevoice", Category::Voice, "Change to different voice",
+            |app, argument| Box::pin(async move { commands::changevoice_command(argument, app.state()).await }))
+            .with_argument(Argument::Required("voice"))
+            .with_items(|app| crate::get_voices(app.state()))
+            .waiting_for_ready()
+            .with_examples(&["/changevoice calm.json"])
+            .with_current(|app| config::get_voice(&app.state()).unwrap_or_default()),
+        BuiltinCommand::new("nextvoice", Category::Voice, "Change to the next voice in the list",
+            |app, _| Box::pin(async move { commands::nextvoice_command(app.clone(), app.state()).await }))
+            .waiting_for_ready()
+            .with_current(|app| config::get_voice(&app.state()).unwrap_or_default()),
+        BuiltinCommand::new("stopspeaking", Category::Voice, "Stop speaking and drop anything queued, keeping the voice loaded",
+            |app, _| Box::pin(commands::stopspeaking_command(app))),
+        BuiltinCommand::new("skip", Category::Voice, "Skip what is being spoken and go on with the next queued text",
+            |app, _| Box::pin(commands::skip_command(app))),
+        BuiltinCommand::new("repeat", Category::Voice, "Speak the last text again",
+            |app, _| Box::pin(async move { commands::repeat_command(app.clone(), app.state()).await })),
+        BuiltinCommand::new("clipboard", Category::Voice, "Speak the text on the clipboard, or watch it and speak anything new that is copied",
+            |app, argument| Box::pin(async move { commands::clipboard_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Optional("watch|ignore"))
+            .with_completer(clipboard::complete_argument)
+            .with_examples(&["/clipboard", "/clipboard watch on", "/clipboard ignore add ^\\d+$"])
+            .with_current(|app| format!("watch {}", if config::get_clipboard_config(&app.state()).watch { "on" } else { "off" })),
+        BuiltinCommand::new("say", Category::Voice, "Speak a saved snippet with {time}, {date}, {clipboard} and {voice} filled in",
+            |app, argument| Box::pin(async move { commands::say_command(argument, app.clone()).await }))
+            .with_argument(Argument::Required("snippet"))
+            .with_items(|app| snippets::snippet_names(&app.state()))
+            .with_examples(&["/say greeting"]),
+        BuiltinCommand::new("clonevoice", Category::Voice, "Clone voice from audio file (opens file dialog)",
+            |app, _| Box::pin(async move { commands::clonevoice_command(app.clone(), app.state()).await }))
+            .waiting_for_ready(),
+
+        // Application settings
+        BuiltinCommand::new("lostfocus", Category::Settings, "Configure window behavior when focus is lost (hide/show)",
+            |app, argument| Box::pin(async move { commands::lostfocus_command(argument, app.state()).await }))
+            .with_argument(Argument::Required("behaviour"))
+            .with_items(|app| crate::get_lostfocus_options(app.state()))
+            .with_examples(&["/lostfocus show"])
+            .with_current(|app| config::get_lostfocus_behaviour(&app.state())),
+        BuiltinCommand::new("theme", Category::Settings, "Switch the overlay to a built-in color theme; size and font scale are kept",
+            |app, argument| Box::pin(async move { commands::theme_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Required("theme"))
+            .with_items(|app| theme::preset_options(&app.state()))
+            .with_examples(&["/theme high-contrast", "/set theme.font_scale 1.25"])
+            .with_current(|app| config::get_theme(&app.state()).preset),
+        BuiltinCommand::new("get", Category::Settings, "Show a setting by its dotted key",
+            |app, argument| Box::pin(async move { commands::get_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Required("key"))
+            .with_completer(settings::complete_get_argument)
+            .with_examples(&["/get tts.volume", "/get window"]),
+        BuiltinCommand::new("set", Category::Settings, "Change any setting and apply it immediately (Tab completes keys and values)",
+            |app, argument| Box::pin(async move { commands::set_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Required("key value"))
+            .with_completer(settings::complete_set_argument)
+            .with_examples(&["/set window.position center", "/set tts.voice_settings.bob.speed 0.9"]),
+        BuiltinCommand::new("alias", Category::Settings, "Define shortcuts that run one or more commands",
+            |app, argument| Box::pin(async move { commands::alias_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Required("add|remove|list"))
+            .with_completer(aliases::complete_argument)
+            .with_examples(&["/alias add tl /topleft", "/alias add meeting /outputdevice Headset; /volume 0.3", "/alias remove tl"]),
+        BuiltinCommand::new("snippet", Category::Settings, "Manage the snippets /say speaks",
+            |app, argument| Box::pin(async move { commands::snippet_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Required("add|remove|list"))
+            .with_completer(snippets::complete_argument)
+            .with_examples(&["/snippet add greeting Good morning, it is {time}", "/snippet remove greeting"]),
+        BuiltinCommand::new("hotkey", Category::Settings, "Rebind a global shortcut, or list them with any registration problems",
+            |app, argument| Box::pin(async move { commands::hotkey_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Optional("action shortcut"))
+            .with_completer(hotkeys::complete_argument)
+            .with_examples(&["/hotkey toggle Ctrl+Shift+Space", "/hotkey list"]),
+        BuiltinCommand::new("export", Category::Settings, "Save config and cloned voices (optionally cached models) into one setup archive",
+            |app, argument| Box::pin(async move { commands::export_command(argument, app.clone(), app.state()).await }))
+            .with_argument(Argument::Optional("with models"))
+            .with_items(|_| crate::get_export_options())
+            .with_examples(&["/export", "/export with models"]),
+        BuiltinCommand::new("import", Category::Settings, "Load a setup archive; voices with clashing names are imported under a new name",
+            |app, _| Box::pin(async move { commands::import_command(app.clone(), app.state()).await })),
+        BuiltinCommand::new("clearhistory", Category::Settings, "Forget everything typed into the overlay",
+            |app, _| Box::pin(async move { commands::clearhistory_command(app.clone(), app.state()).await })),
+        BuiltinCommand::new("undo", Category::Settings, "Revert the last settings change and re-apply the previous values",
+            |app, _| Box::pin(async move { commands::undo_command(app.clone(), app.state()).await })),
+        BuiltinCommand::new("redo", Category::Settings, "Re-apply the last undone settings change",
+            |app, _| Box::pin(async move { commands::redo_command(app.clone(), app.state()).await })),
+        BuiltinCommand::new("resetconfig", Category::Settings, "Reset all settings to defaults",
+            |app, _| Box::pin(async move { commands::resetconfig_command(app.clone(), app.state()).await })),
+        BuiltinCommand::new("restartserver", Category::Settings, "Restart the Python TTS backend",
+            |app, _| Box::pin(async move { commands::restartserver_command(app.state()).await }))
+            .waiting_for_ready(),
+        BuiltinCommand::new("exit", Category::Settings, "Close the application",
+            |app, _| Box::pin(async move { commands::exit_command(app.clone(), app.state()).await }))
+            .with_aliases(&["quit"]),
+        BuiltinCommand::new("help", Category::Settings, "Open the help window, or describe one command in the overlay",
+            |app, argument| Box::pin(commands::help_command(argument, app)))
+            .with_argument(Argument::Optional("command"))
+            .with_completer(help::complete_argument)
+            .with_examples(&["/help", "/help volume"]),
+
+        // System
+        BuiltinCommand::new("ready", Category::System, "Send manual ready signal to backend",
+            |_, _| Box::pin(commands::ready_command()))
+            .waiting_for_ready(),
+    ];
+
+    builtins
+        .into_iter()
+        .map(|command| Box::new(command) as Box<dyn Command>)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Stands in for a loaded plugin
+    struct FakePlugin(&'static str);
+
+    impl Command for FakePlugin {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "A plugin"
+        }
+
+        fn category(&self) -> Category {
+            Category::Plugins
+        }
+
+        fn argument(&self) -> Argument<'_> {
+            Argument::Optional("city")
+        }
+
+        fn run(&self, _app: tauri::AppHandle, _argument: Option<String>) -> CommandFuture {
+            Box::pin(async { Ok(String::new()) })
+        }
+    }
+
+    fn registry_with(plugins: &[&'static str]) -> CommandRegistry {
+        let plugins = plugins
+            .iter()
+            .map(|name| Box::new(FakePlugin(*name)) as Box<dyn Command>)
+            .collect();
+        CommandRegistry::with_plugins(builtin_commands(), plugins)
+    }
+
+    fn plugin_names(registry: &CommandRegistry) -> Vec<&str> {
+        registry
+            .iter()
+            .filter(|command| matches!(command.category(), Category::Plugins))
+            .map(|command| command.name())
+            .collect()
+    }
+
+    #[test]
+    fn plugins_come_after_the_builtins() {
+        let registry = registry_with(&["weather", "news"]);
+        assert_eq!(registry.iter().count(), builtin_commands().len() + 2);
+        assert_eq!(plugin_names(&registry), vec!["weather", "news"]);
+        assert_eq!(registry.find("weather").map(|command| command.description()), Some("A plugin"));
+    }
+
+    #[test]
+    fn plugins_clashing_with_a_builtin_name_or_alias_are_skipped() {
+        let registry = registry_with(&["stop", "quit", "weather", "weather"]);
+        assert_eq!(plugin_names(&registry), vec!["weather"]);
+        assert!(matches!(registry.find("stop").map(|command| command.category()), Some(Category::Voice)));
+        assert_eq!(registry.find("quit").map(|command| command.name()), Some("exit"));
+    }
+
+    #[test]
+    fn names_list_every_name_and_alias_once() {
+        let names = registry_with(&["weather"]).names();
+        let exit = names.iter().position(|name| name == "exit").unwrap();
+        assert_eq!(names[exit + 1], "quit");
+        assert_eq!(names.last().map(String::as_str), Some("weather"));
+        for name in &names {
+            assert_eq!(names.iter().filter(|other| *other == name).count(), 1, "{} is listed twice", name);
+        }
+    }
+
+    #[test]
+    fn info_describes_builtins_and_plugins() {
+        let registry = registry_with(&["weather"]);
+
+        let changevoice = command_info(registry.find("changevoice").unwrap(), Some("amy.json".to_string()), false);
+        assert_eq!(changevoice.usage, "/changevoice <voice>");
+        assert_eq!(changevoice.category, "Voice Management");
+        assert_eq!(changevoice.current_value.as_deref(), Some("amy.json"));
+        assert!(changevoice.has_items);
+        assert!(!changevoice.multi_select);
+
+        let exit = command_info(registry.find("quit").unwrap(), None, false);
+        assert_eq!(exit.name, "exit");
+        assert_eq!(exit.aliases, vec!["quit"]);
+        assert_eq!(exit.usage, "/exit");
+
+        let weather = command_info(registry.find("weather").unwrap(), None, false);
+        assert_eq!(weather.usage, "/weather [city]");
+        assert_eq!(weather.category, "Plugin Commands");
+        assert!(!weather.has_items && !weather.waits_for_ready);
+    }
+}
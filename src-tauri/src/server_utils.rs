@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use std::process::{Command, Child, Stdio};
 use std::fs::OpenOptions;
-use tauri::{State, Emitter};
+use tauri::{Emitter, Manager, State};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use futures_util::{StreamExt};
 
@@ -53,20 +53,56 @@ pub fn stop_server(state: State<crate::AppState>) {
     }
 }
 
-pub async fn emit_ready(app_handle: tauri::AppHandle) {
-    crate::log::tauri_websocket_log("Ready!");
+fn send_ready(app_handle: &tauri::AppHandle) {
     if let Err(e) = app_handle.emit("ready", ()) {
         crate::log::tauri_log(&format!("Failed to emit ready event: {}", e));
     }
 }
 
-pub async fn emit_notification(app_handle: tauri::AppHandle, message: String) {
-    crate::log::tauri_websocket_log(&format!("Notification: {}", message));
+fn send_notification(app_handle: &tauri::AppHandle, message: String) {
     if let Err(e) = app_handle.emit("notification", message) {
         crate::log::tauri_log(&format!("Failed to emit notification event: {}", e));
     }
 }
 
+pub async fn emit_ready(app_handle: tauri::AppHandle) {
+    crate::log::tauri_websocket_log("Ready!");
+    let state = app_handle.state::<crate::AppState>();
+    state.chain.signal_ready();
+    // A running chain reports readiness once, after its last step
+    if state.chain.in_run() {
+        return;
+    }
+    send_ready(&app_handle);
+}
+
+pub async fn emit_notification(app_handle: tauri::AppHandle, message: String) {
+    crate::log::tauri_websocket_log(&format!("Notification: {}", message));
+    if app_handle.state::<crate::AppState>().chain.collect_notification(&message) {
+        return;
+    }
+    send_notification(&app_handle, message);
+}
+
+// Ready signals and notifications from the websocket, which belong to a running chain if there is one
+async fn emit_backend_ready(app_handle: tauri::AppHandle) {
+    crate::log::tauri_websocket_log("Ready!");
+    let state = app_handle.state::<crate::AppState>();
+    state.chain.signal_ready();
+    if state.chain.owns_backend() {
+        return;
+    }
+    send_ready(&app_handle);
+}
+
+async fn emit_backend_notification(app_handle: tauri::AppHandle, message: String) {
+    crate::log::tauri_websocket_log(&format!("Notification: {}", message));
+    if app_handle.state::<crate::AppState>().chain.collect_backend_notification(&message) {
+        return;
+    }
+    send_notification(&app_handle, message);
+}
+
 pub async fn websocket_listener(app_handle: tauri::AppHandle) {
     tokio::spawn(async move {
         loop {
@@ -78,10 +114,10 @@ pub async fn websocket_listener(app_handle: tauri::AppHandle) {
                         match msg {
                             Ok(Message::Text(text)) => {
                                 if text == "ready" {
-                                    emit_ready(app_handle.clone()).await;
+                                    emit_backend_ready(app_handle.clone()).await;
                                 } else if text.starts_with("notification ") {
                                     let message = text.strip_prefix("notification ").unwrap_or("").to_string();
-                                    emit_notification(app_handle.clone(), message).await;
+                                    emit_backend_notification(app_handle.clone(), message).await;
                                 }
                             }
                             Ok(Message::Close(_)) => {
@@ -0,0 +1,447 @@
+use serde_json::Value;
+use tauri::{Manager, State};
+use crate::{AppState, aliases, audio, bridge, config, hotkeys, layout, monitors, server_utils, theme, utils};
+use crate::config::BoxtsConfig;
+use crate::registry::ArgumentCompletion;
+
+const LOST_FOCUS_BEHAVIOURS: &[&str] = &["hide", "show"];
+const BOOLEAN_VALUES: &[&str] = &["true", "false"];
+const XTTS_LANGUAGES: &[&str] = &["en", "es", "fr", "de", "it", "pt", "pl", "tr", "ru", "nl", "cs", "ar", "zh-cn", "ja", "hu", "ko", "hi"];
+
+// Extra validation for keys whose type alone doesn't describe the valid values
+enum Constraint {
+    Range(f64, f64),
+    Choices(&'static [&'static str]),
+    Voices,
+    Monitors,
+    Offset,
+    Shortcut,
+    Color,
+}
+
+fn constraint_for(key: &str) -> Option<Constraint> {
+    // Per-voice entries are keyed by voice name, so match on the parameter alone
+    if let Some(rest) = key.strip_prefix("tts.voice_settings.") {
+        return match rest.rsplit('.').next() {
+            Some("speed") => Some(Constraint::Range(0.25, 4.0)),
+            Some("temperature") => Some(Constraint::Range(0.01, 2.0)),
+            Some("language") => Some(Constraint::Choices(XTTS_LANGUAGES)),
+            Some("repetition_penalty") => Some(Constraint::Range(1.0, 20.0)),
+            Some("volume") => Some(Constraint::Range(0.0, 2.0)),
+            _ => None,
+        };
+    }
+
+    match key {
+        "window.position" => Some(Constraint::Choices(layout::ANCHORS)),
+        "window.offset_x" | "window.offset_y" => Some(Constraint::Offset),
+        "window.monitor_id" => Some(Constraint::Monitors),
+        "window.lost_focus_behaviour" => Some(Constraint::Choices(LOST_FOCUS_BEHAVIOURS)),
+        "tts.volume" => Some(Constraint::Range(0.0, 1.0)),
+        "tts.voice" => Some(Constraint::Voices),
+        "history.max_entries" => Some(Constraint::Range(0.0, 10000.0)),
+        "clipboard.max_length" => Some(Constraint::Range(1.0, 100000.0)),
+        "clipboard.dedup_seconds" => Some(Constraint::Range(0.0, 86400.0)),
+        key if key.starts_with("hotkeys.") => Some(Constraint::Shortcut),
+        "theme.preset" => Some(Constraint::Choices(theme::PRESETS)),
+        "theme.opacity" => Some(Constraint::Range(0.0, 1.0)),
+        "theme.width" => Some(Constraint::Range(200.0, 3840.0)),
+        "theme.height" | "theme.expanded_height" => Some(Constraint::Range(40.0, 2160.0)),
+        "theme.font_scale" => Some(Constraint::Range(0.5, 3.0)),
+        key if key.starts_with("theme.") => Some(Constraint::Color),
+        _ => None,
+    }
+}
+
+fn config_to_value(config: &BoxtsConfig) -> Result<Value, String> {
+    serde_json::to_value(config).map_err(|e| format!("Failed to read config: {}", e))
+}
+
+// Lists are only included for comparing configs, /set can't edit them
+fn collect_keys(value: &Value, prefix: &str, with_lists: bool, keys: &mut Vec<String>) {
+    if let Value::Object(map) = value {
+        for (name, child) in map {
+            let key = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", prefix, name)
+            };
+            match child {
+                Value::Object(_) => collect_keys(child, &key, with_lists, keys),
+                Value::Array(_) if with_lists => keys.push(key),
+                Value::Array(_) | Value::Null => {}
+                _ => keys.push(key),
+            }
+        }
+    }
+}
+
+// Voice names may contain dots (my.voice.json), so below tts.voice_settings the last segment is
+// the parameter unless the whole rest already names a voice
+fn segments(value: &Value, key: &str) -> Vec<String> {
+    let rest = match key.strip_prefix("tts.voice_settings.") {
+        Some(rest) => rest,
+        None => return key.split('.').map(str::to_string).collect(),
+    };
+
+    let mut segments = vec!["tts".to_string(), "voice_settings".to_string()];
+    let is_voice = value.pointer("/tts/voice_settings").and_then(|voices| voices.get(rest)).is_some();
+    match rest.rsplit_once('.') {
+        Some((voice, parameter)) if !is_voice => segments.extend([voice.to_string(), parameter.to_string()]),
+        _ => segments.push(rest.to_string()),
+    }
+    segments
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    segments(value, key).iter().try_fold(value, |current, segment| current.get(segment))
+}
+
+fn lookup_mut<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    segments(value, key).iter().try_fold(value, |current, segment| current.get_mut(segment))
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        // Config floats are f32, so print them at that precision to avoid 0.30000001192092896
+        Value::Number(n) if n.is_f64() => format!("{}", n.as_f64().unwrap_or_default() as f32),
+        Value::Object(map) => map
+            .iter()
+            .map(|(name, child)| format!("{}: {}", name, format_value(child)))
+            .collect::<Vec<String>>()
+            .join(", "),
+        Value::Array(items) => items.iter().map(format_value).collect::<Vec<String>>().join(" | "),
+        other => other.to_string(),
+    }
+}
+
+fn parse_value(current: &Value, raw: &str) -> Result<Value, String> {
+    match current {
+        Value::String(_) => Ok(Value::String(raw.to_string())),
+        Value::Bool(_) => match raw.to_lowercase().as_str() {
+            "true" | "on" | "yes" | "1" => Ok(Value::Bool(true)),
+            "false" | "off" | "no" | "0" => Ok(Value::Bool(false)),
+            _ => Err(format!("Expected true or false, got: {}", raw)),
+        },
+        Value::Number(n) if n.is_f64() => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("Expected a number, got: {}", raw)),
+        Value::Number(n) if n.is_u64() => raw
+            .parse::<u64>()
+            .map(Value::from)
+            .map_err(|_| format!("Expected a whole number, got: {}", raw)),
+        Value::Number(_) => raw
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| format!("Expected a whole number, got: {}", raw)),
+        _ => Err("This setting can't be changed with /set".to_string()),
+    }
+}
+
+fn allowed_values(app: &tauri::AppHandle, key: &str, current: &Value) -> Vec<String> {
+    match constraint_for(key) {
+        Some(Constraint::Choices(choices)) => choices.iter().map(|s| s.to_string()).collect(),
+        Some(Constraint::Voices) => utils::list_voices(),
+        Some(Constraint::Monitors) => app
+            .available_monitors()
+            .map(|monitors| (0..monitors.len()).map(|i| i.to_string()).collect())
+            .unwrap_or_default(),
+        Some(Constraint::Range(_, _)) | Some(Constraint::Offset) | Some(Constraint::Shortcut) | Some(Constraint::Color) | None => match current {
+            Value::Bool(_) => BOOLEAN_VALUES.iter().map(|s| s.to_string()).collect(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+fn validate(app: &tauri::AppHandle, key: &str, value: &Value) -> Result<(), String> {
+    if let Some(Constraint::Offset) = constraint_for(key) {
+        return layout::parse_offset(&format_value(value)).map(|_| ());
+    }
+    if let Some(Constraint::Color) = constraint_for(key) {
+        return theme::parse_color(&format_value(value));
+    }
+    if let Some(Constraint::Shortcut) = constraint_for(key) {
+        // Same checks as /hotkey, so a clash is refused instead of unbinding the other action
+        let hotkeys = app.state::<AppState>().config.lock().unwrap().hotkeys.clone();
+        let action = key.trim_start_matches("hotkeys.");
+        return hotkeys::check_binding(&hotkeys, action, &format_value(value)).map(|_| ());
+    }
+    if let Some(Constraint::Range(min, max)) = constraint_for(key) {
+        let number = value.as_f64().unwrap_or_default();
+        if number < min || number > max {
+            return Err(format!("{} must be between {} and {}", key, min, max));
+        }
+        return Ok(());
+    }
+
+    let allowed = allowed_values(app, key, value);
+    // An empty list means the options couldn't be enumerated, so accept the value as-is
+    if !allowed.is_empty() && !allowed.contains(&format_value(value)) {
+        return Err(format!("Invalid value for {}. Expected one of: {}", key, allowed.join(", ")));
+    }
+
+    Ok(())
+}
+
+// Every dotted key whose value differs, with the old and new value ("unset" when missing)
+pub fn changed_keys(before: &BoxtsConfig, after: &BoxtsConfig) -> Vec<(String, String, String)> {
+    let (before, after) = match (config_to_value(before), config_to_value(after)) {
+        (Ok(before), Ok(after)) => (before, after),
+        _ => return Vec::new(),
+    };
+
+    let mut keys = Vec::new();
+    collect_keys(&before, "", true, &mut keys);
+    collect_keys(&after, "", true, &mut keys);
+    keys.sort();
+    keys.dedup();
+
+    let describe = |tree: &Value, key: &str| lookup(tree, key).map(format_value).unwrap_or_else(|| "unset".to_string());
+    keys.into_iter()
+        .filter(|key| lookup(&before, key) != lookup(&after, key))
+        .map(|key| {
+            let old = describe(&before, &key);
+            let new = describe(&after, &key);
+            (key, old, new)
+        })
+        .collect()
+}
+
+pub fn get_value(state: &State<AppState>, key: &str) -> Result<String, String> {
+    let tree = {
+        let config = state.config.lock().unwrap();
+        config_to_value(&config)?
+    };
+
+    lookup(&tree, key)
+        .map(format_value)
+        .ok_or_else(|| format!("Unknown setting: {}", key))
+}
+
+pub fn set_value(app: &tauri::AppHandle, state: &State<AppState>, key: &str, raw: &str) -> Result<String, String> {
+    let current = {
+        let config = state.config.lock().unwrap();
+        let tree = config_to_value(&config)?;
+        lookup(&tree, key)
+            .cloned()
+            .ok_or_else(|| format!("Unknown setting: {}", key))?
+    };
+
+    let value = parse_value(&current, raw)?;
+    validate(app, key, &value)?;
+
+    let mut config = state.config.lock().unwrap();
+    let mut tree = config_to_value(&config)?;
+    if let Some(slot) = lookup_mut(&mut tree, key) {
+        *slot = value.clone();
+    }
+
+    // Round-tripping through BoxtsConfig rejects anything that no longer fits the schema
+    *config = serde_json::from_value(tree).map_err(|e| format!("Invalid value for {}: {}", key, e))?;
+    if key == "theme.preset" {
+        // Picking a preset by name brings in its colors
+        config.theme = theme::with_preset(&config.theme, &format_value(&value))?;
+    } else if key == "theme.opacity" || matches!(constraint_for(key), Some(Constraint::Color)) {
+        // The palette no longer matches the preset it came from
+        config.theme.preset = theme::CUSTOM_PRESET.to_string();
+    }
+    config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+
+    Ok(format_value(&value))
+}
+
+// `previous` is the value the key had before, as /get shows it
+pub async fn apply_setting(app: tauri::AppHandle, state: State<'_, AppState>, key: &str, previous: &str) -> Result<(), String> {
+    // The Python server reads TTS settings straight from the config file
+    if key.starts_with("tts.") {
+        config::flush_config(&state).await;
+    }
+
+    match key {
+        "window.position" | "window.offset_x" | "window.offset_y" => {
+            let position = config::get_window_position(&state);
+            utils::apply_window_position(app, state, &position).await?;
+        },
+        "window.monitor_id" => {
+            let monitor_id = state.config.lock().unwrap().window.monitor_id;
+            monitors::switch_to_monitor(app, state, monitor_id as usize).await?;
+        },
+        key if key.starts_with("hotkeys.") => {
+            let action = key.trim_start_matches("hotkeys.");
+            if let Err(e) = hotkeys::register_binding(&app, &state, action, previous) {
+                // The binding that couldn't be taken was already saved, so save the rollback too
+                let config = state.config.lock().unwrap();
+                config::save_config(&state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+                return Err(e);
+            }
+        },
+        key if key.starts_with("theme.") => {
+            theme::apply_theme_and_position(app, state).await?;
+        },
+        "window.follow_cursor" => {
+            if !config::get_follow_cursor(&state) {
+                monitors::apply_saved_monitor(app, state).await?;
+            }
+        },
+        key if key.starts_with("window.monitor.") => {
+            monitors::apply_saved_monitor(app, state).await?;
+        },
+        "tts.volume" => {
+            bridge::send_volume_request().await?;
+        },
+        "history.enabled" => {
+            if !state.config.lock().unwrap().history.enabled {
+                state.history.lock().unwrap().clear();
+            }
+        },
+        "history.max_entries" => {
+            let max_entries = state.config.lock().unwrap().history.max_entries;
+            state.history.lock().unwrap().apply_limit(max_entries);
+        },
+        // /set can't edit lists, so this runs when undo, redo, /import or /resetconfig change the
+        // devices; /outputdevice sends its own request
+        "tts.output_devices" => {
+            let resolved = audio::targets_for(&config::get_output_devices(&state))?;
+            let substitution = resolved.substitution();
+            bridge::send_outputdevice_request(resolved.targets).await?;
+            if let Some(message) = substitution {
+                server_utils::emit_notification(app.clone(), message).await;
+            }
+        },
+        "tts.voice" => {
+            let voice = config::get_voice(&state).map_err(|e| e.to_string())?;
+            let voice_settings = config::get_voice_settings(&state, &voice);
+            bridge::send_changevoice_request(voice, voice_settings).await?;
+        },
+        _ => {
+            // Only the active voice's settings need pushing to the running engine
+            if let Some(rest) = key.strip_prefix("tts.voice_settings.") {
+                let voice = config::get_voice(&state).map_err(|e| e.to_string())?;
+                let voice_key = config::voice_settings_key(&voice);
+                if rest.rsplit_once('.').is_some_and(|(name, _)| name == voice_key) {
+                    bridge::send_voicesettings_request(config::get_voice_settings(&state, &voice)).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Applies every key from changed_keys, carrying on past failures so no key is left saved but
+// unapplied; the failures are reported together
+pub async fn apply_changes(app: tauri::AppHandle, state: State<'_, AppState>, changed: &[(String, String, String)]) -> Result<(), String> {
+    let mut problems = Vec::new();
+    for (key, previous, _) in changed {
+        if let Err(e) = apply_setting(app.clone(), state.clone(), key, previous).await {
+            problems.push(format!("{}: {}", key, e));
+        }
+    }
+
+    if changed.iter().any(|(key, _, _)| key.starts_with("aliases.")) {
+        aliases::emit_commands_changed(&app);
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join(" • "))
+    }
+}
+
+// Completes the key for /get, and the key then the value for /set
+fn complete_argument(app: &tauri::AppHandle, argument: &str, with_value: bool) -> ArgumentCompletion {
+    let state = app.state::<AppState>();
+    let tree = {
+        let config = state.config.lock().unwrap();
+        match config_to_value(&config) {
+            Ok(tree) => tree,
+            Err(_) => return ArgumentCompletion::default(),
+        }
+    };
+
+    match argument.split_once(' ') {
+        None => {
+            let mut keys = Vec::new();
+            collect_keys(&tree, "", false, &mut keys);
+            ArgumentCompletion { offset: 0, values: keys }
+        },
+        Some((key, _)) if with_value => {
+            let current = match lookup(&tree, key) {
+                Some(current) => current,
+                None => return ArgumentCompletion::default(),
+            };
+
+            let mut values = allowed_values(app, key, current);
+
+            // Put current value first, like the item selectors do
+            let current_str = format_value(current);
+            if let Some(current_index) = values.iter().position(|v| v == &current_str) {
+                values.rotate_left(current_index);
+            }
+
+            ArgumentCompletion { offset: key.len() + 1, values }
+        },
+        _ => ArgumentCompletion::default(),
+    }
+}
+
+pub fn complete_get_argument(app: &tauri::AppHandle, argument: &str) -> ArgumentCompletion {
+    complete_argument(app, argument, false)
+}
+
+pub fn complete_set_argument(app: &tauri::AppHandle, argument: &str) -> ArgumentCompletion {
+    complete_argument(app, argument, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tree() -> Value {
+        json!({
+            "tts": {
+                "volume": 0.8,
+                "voice_settings": {
+                    "bob": { "speed": 1.0 },
+                    "my.voice": { "speed": 1.5 },
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn looks_up_plain_keys() {
+        assert_eq!(lookup(&tree(), "tts.volume"), Some(&json!(0.8)));
+        assert_eq!(lookup(&tree(), "tts.voice_settings.bob.speed"), Some(&json!(1.0)));
+        assert_eq!(lookup(&tree(), "tts.pitch"), None);
+    }
+
+    #[test]
+    fn looks_up_voices_with_dots_in_their_name() {
+        assert_eq!(lookup(&tree(), "tts.voice_settings.my.voice.speed"), Some(&json!(1.5)));
+        assert_eq!(lookup(&tree(), "tts.voice_settings.my.voice"), Some(&json!({ "speed": 1.5 })));
+        assert_eq!(lookup(&tree(), "tts.voice_settings.my.speed"), None);
+    }
+
+    #[test]
+    fn sets_voices_with_dots_in_their_name() {
+        let mut tree = tree();
+        *lookup_mut(&mut tree, "tts.voice_settings.my.voice.speed").unwrap() = json!(0.5);
+        assert_eq!(tree["tts"]["voice_settings"]["my.voice"]["speed"], json!(0.5));
+    }
+
+    #[test]
+    fn lists_keys_as_get_and_set_take_them() {
+        let mut keys = Vec::new();
+        collect_keys(&tree(), "", false, &mut keys);
+        for key in &keys {
+            assert!(lookup(&tree(), key).is_some(), "{} can't be looked up", key);
+        }
+        assert!(keys.contains(&"tts.voice_settings.my.voice.speed".to_string()));
+    }
+}
@@ -0,0 +1,160 @@
+use tauri::{Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use crate::{AppState, config};
+use crate::registry::ArgumentCompletion;
+
+const SNIPPET_ACTIONS: &[&str] = &["add", "remove", "list"];
+
+// Replaces {name} with its value and {{ or }} with a literal brace; unknown names and
+// stray braces are left as typed
+fn render(template: &str, variable: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '}']) {
+        output.push_str(&rest[..index]);
+        let brace = if rest[index..].starts_with('{') { '{' } else { '}' };
+        let after = &rest[index + 1..];
+        if let Some(escaped) = after.strip_prefix(brace) {
+            output.push(brace);
+            rest = escaped;
+            continue;
+        }
+        if brace == '{' {
+            let filled = after.find('}').and_then(|close| Some((close, variable(&after[..close])?)));
+            if let Some((close, value)) = filled {
+                output.push_str(&value);
+                rest = &after[close + 1..];
+                continue;
+            }
+        }
+        output.push(brace);
+        rest = after;
+    }
+    output.push_str(rest);
+    output
+}
+
+fn variable(app: &tauri::AppHandle, name: &str) -> Option<String> {
+    match name {
+        "time" => Some(chrono::Local::now().format("%H:%M").to_string()),
+        "date" => Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
+        "clipboard" => Some(app.clipboard().read_text().unwrap_or_default()),
+        "voice" => {
+            let voice = config::get_voice(&app.state()).unwrap_or_default();
+            Some(config::voice_settings_key(&voice))
+        },
+        _ => None,
+    }
+}
+
+pub fn snippet_names(state: &State<AppState>) -> Vec<String> {
+    state.config.lock().unwrap().snippets.keys().cloned().collect()
+}
+
+// The text /say <name> speaks, with its variables filled in
+pub fn expand_snippet(app: &tauri::AppHandle, name: &str) -> Result<String, String> {
+    let template = app
+        .state::<AppState>()
+        .config
+        .lock()
+        .unwrap()
+        .snippets
+        .get(name)
+        .cloned()
+        .ok_or_else(|| format!("No snippet named {}", name))?;
+
+    Ok(render(&template, &|variable_name| variable(app, variable_name)))
+}
+
+pub fn add_snippet(state: &State<AppState>, name: &str, text: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid snippet name: {}", name));
+    }
+    if text.trim().is_empty() {
+        return Err(format!("Snippet {} needs some text", name));
+    }
+
+    let mut config = state.config.lock().unwrap();
+    config.snippets.insert(name.to_string(), text.trim().to_string());
+    config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))
+}
+
+pub fn remove_snippet(state: &State<AppState>, name: &str) -> Result<(), String> {
+    let mut config = state.config.lock().unwrap();
+    if config.snippets.remove(name).is_none() {
+        return Err(format!("No snippet named {}", name));
+    }
+    config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))
+}
+
+pub fn list_snippets(state: &State<AppState>) -> String {
+    let config = state.config.lock().unwrap();
+    if config.snippets.is_empty() {
+        return "No snippets defined".to_string();
+    }
+
+    config.snippets
+        .iter()
+        .map(|(name, text)| format!("{} = {}", name, text))
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+// Completes the action, then the snippet name for /snippet remove
+pub fn complete_argument(app: &tauri::AppHandle, argument: &str) -> ArgumentCompletion {
+    match argument.split_once(' ') {
+        None => ArgumentCompletion {
+            offset: 0,
+            values: SNIPPET_ACTIONS.iter().map(|action| action.to_string()).collect(),
+        },
+        Some(("remove", _)) => ArgumentCompletion {
+            offset: "remove ".len(),
+            values: snippet_names(&app.state()),
+        },
+        _ => ArgumentCompletion::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_with_stub(template: &str) -> String {
+        render(template, &|name| match name {
+            "time" => Some("12:30".to_string()),
+            "voice" => Some("amy".to_string()),
+            "empty" => Some(String::new()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn fills_in_known_variables() {
+        assert_eq!(render_with_stub("It is {time}, this is {voice}."), "It is 12:30, this is amy.");
+        assert_eq!(render_with_stub("{time}{time}"), "12:3012:30");
+        assert_eq!(render_with_stub("[{empty}]"), "[]");
+        assert_eq!(render_with_stub("no variables at all"), "no variables at all");
+    }
+
+    #[test]
+    fn leaves_unknown_names_and_stray_braces_as_typed() {
+        assert_eq!(render_with_stub("{nothing} here"), "{nothing} here");
+        assert_eq!(render_with_stub("open { only"), "open { only");
+        assert_eq!(render_with_stub("close } only"), "close } only");
+        assert_eq!(render_with_stub("{ {time}"), "{ 12:30");
+        assert_eq!(render_with_stub("{a{time}"), "{a12:30");
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(render_with_stub("{{time}}"), "{time}");
+        assert_eq!(render_with_stub("{{time}"), "{time}");
+        assert_eq!(render_with_stub("{{{time}}}"), "{12:30}");
+        assert_eq!(render_with_stub("a }} b"), "a } b");
+    }
+
+    #[test]
+    fn keeps_multibyte_text_intact() {
+        assert_eq!(render_with_stub("über {voice} • café"), "über amy • café");
+    }
+}
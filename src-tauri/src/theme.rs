@@ -0,0 +1,118 @@
+use tauri::{Emitter, LogicalSize, Manager, Size, State};
+use crate::{AppState, utils};
+use crate::config::{self, ThemeConfig};
+
+// Built-in palettes; picking one overwrites the colors and opacity, not the size or font scale
+pub const PRESETS: &[&str] = &["dark", "light", "high-contrast", "midnight"];
+const DEFAULT_PRESET: &str = "dark";
+// theme.preset once a color or the opacity was changed by hand; not a preset to pick
+pub const CUSTOM_PRESET: &str = "custom";
+
+// Matches the window in tauri.conf.json, so nothing jumps at startup
+const DEFAULT_WIDTH: u32 = 444;
+const DEFAULT_HEIGHT: u32 = 54;
+const DEFAULT_EXPANDED_HEIGHT: u32 = 220;
+
+pub fn preset(name: &str) -> Option<ThemeConfig> {
+    let (background, text, border, suggestion, error, item_primary, item_secondary, opacity) = match name {
+        "dark" => ("#131313", "#eeeeee", "#535353", "#79f079", "#ff6b6b", "#00ffea", "#fffb00", 0.5),
+        "light" => ("#f4f4f4", "#1e1e1e", "#b0b0b0", "#1d8a3a", "#c62828", "#00796b", "#8d6e00", 0.85),
+        // Opaque, pure black and white with saturated accents for low vision
+        "high-contrast" => ("#000000", "#ffffff", "#ffff00", "#00ff00", "#ff4040", "#00ffff", "#ffff00", 1.0),
+        "midnight" => ("#0b1020", "#d6deff", "#3a4a7a", "#8fb8ff", "#ff7a90", "#7ee0ff", "#ffd27e", 0.6),
+        _ => return None,
+    };
+
+    Some(ThemeConfig {
+        preset: name.to_string(),
+        background: background.to_string(),
+        text: text.to_string(),
+        border: border.to_string(),
+        suggestion: suggestion.to_string(),
+        error: error.to_string(),
+        item_primary: item_primary.to_string(),
+        item_secondary: item_secondary.to_string(),
+        opacity,
+        width: DEFAULT_WIDTH,
+        height: DEFAULT_HEIGHT,
+        expanded_height: DEFAULT_EXPANDED_HEIGHT,
+        font_scale: 1.0,
+    })
+}
+
+pub fn default_theme() -> ThemeConfig {
+    preset(DEFAULT_PRESET).expect("the default theme is a preset")
+}
+
+// The overlay appends an alpha byte to the background, so only #rrggbb works
+pub fn parse_color(raw: &str) -> Result<(), String> {
+    let raw = raw.trim();
+    let valid = raw.len() == 7 && raw.starts_with('#') && raw[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid color: {} (use e.g. #1e1e1e)", raw))
+    }
+}
+
+// Keeps the size and font scale, takes everything else from the preset
+pub fn with_preset(current: &ThemeConfig, name: &str) -> Result<ThemeConfig, String> {
+    let preset = preset(name).ok_or_else(|| format!("Unknown theme: {} (choose from {})", name, PRESETS.join(", ")))?;
+    Ok(ThemeConfig {
+        width: current.width,
+        height: current.height,
+        expanded_height: current.expanded_height,
+        font_scale: current.font_scale,
+        ..preset
+    })
+}
+
+pub fn set_preset(state: &State<AppState>, name: &str) -> Result<(), String> {
+    let mut config = state.config.lock().unwrap();
+    config.theme = with_preset(&config.theme, name)?;
+    config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))
+}
+
+// Resizes the overlay and tells it to redraw; callers re-apply the anchor afterwards
+pub fn apply_theme(app: &tauri::AppHandle, state: &State<AppState>) -> Result<(), String> {
+    let theme = config::get_theme(state);
+    let height = if *state.expanded.lock().unwrap() { theme.expanded_height } else { theme.height };
+    let window = app.get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    window.set_size(Size::Logical(LogicalSize::new(theme.width as f64, height as f64)))
+        .map_err(|e| format!("Failed to resize window: {}", e))?;
+
+    if let Err(e) = app.emit("theme-changed", theme) {
+        crate::log::tauri_log(&format!("Failed to emit theme-changed event: {}", e));
+    }
+    Ok(())
+}
+
+// Resizing moves the window's far edges, so the anchor has to be applied again
+pub async fn apply_theme_and_position(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    apply_theme(&app, &state)?;
+    let position = config::get_window_position(&state);
+    utils::apply_window_position(app, state, &position).await?;
+    Ok(())
+}
+
+// Presets for the item selector, the current one first
+pub fn preset_options(state: &State<AppState>) -> Vec<String> {
+    let mut presets: Vec<String> = PRESETS.iter().map(|preset| preset.to_string()).collect();
+    let current = config::get_theme(state).preset;
+    if let Some(index) = presets.iter().position(|preset| *preset == current) {
+        presets.rotate_left(index);
+    }
+    presets
+}
+
+// Grows the overlay into a multi-line editor, or back, on the same monitor and anchor
+pub async fn set_expanded(app: tauri::AppHandle, state: State<'_, AppState>, expanded: bool) -> Result<(), String> {
+    *state.expanded.lock().unwrap() = expanded;
+    apply_theme_and_position(app.clone(), state).await?;
+
+    if let Err(e) = app.emit("expanded-changed", expanded) {
+        crate::log::tauri_log(&format!("Failed to emit expanded-changed event: {}", e));
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use std::cell::RefCell;
+use std::future::Future;
+use tauri::State;
+use crate::{AppState, config, settings};
+use crate::config::BoxtsConfig;
+
+const UNDO_LIMIT: usize = 50;
+
+#[derive(Clone)]
+pub struct Change {
+    pub description: String,
+    pub before: BoxtsConfig,
+    pub after: BoxtsConfig,
+}
+
+tokio::task_local! {
+    // The latest config the command running on this task saved, if it saved anything
+    static COMMAND_SAVE: RefCell<Option<BoxtsConfig>>;
+}
+
+// Config changes made by commands, one undo step per command. Saves from background tasks
+// (dragging, monitor and device watchers) happen outside of any command and are not recorded.
+pub struct UndoHistory {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+fn describe(before: &BoxtsConfig, after: &BoxtsConfig) -> String {
+    let changes = settings::changed_keys(before, after);
+    match changes.as_slice() {
+        [(key, old, new)] => format!("{} {} → {}", key, old, new),
+        _ => {
+            let keys: Vec<&str> = changes.iter().take(3).map(|(key, _, _)| key.as_str()).collect();
+            let more = if changes.len() > keys.len() { ", …" } else { "" };
+            format!("{} settings ({}{})", changes.len(), keys.join(", "), more)
+        }
+    }
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, before: BoxtsConfig, after: BoxtsConfig) {
+        // A command can end up where it started, e.g. toggling something twice
+        if before == after {
+            return;
+        }
+
+        self.undo.push(Change {
+            description: describe(&before, &after),
+            before,
+            after,
+        });
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn take_undo(&mut self) -> Option<Change> {
+        let change = self.undo.pop()?;
+        self.redo.push(change.clone());
+        Some(change)
+    }
+
+    pub fn take_redo(&mut self) -> Option<Change> {
+        let change = self.redo.pop()?;
+        self.undo.push(change.clone());
+        Some(change)
+    }
+}
+
+async fn apply_restored(app: tauri::AppHandle, state: State<'_, AppState>, target: BoxtsConfig, changed: &[(String, String, String)]) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().unwrap();
+        *config = target;
+        config::save_config(&state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+    }
+    // Every key is applied even if one fails, the whole config was saved already
+    settings::apply_changes(app, state, changed).await
+}
+
+// Called by save_config; false when no command is running on this task, so nothing is recorded
+pub fn note_save(config: &BoxtsConfig) -> bool {
+    COMMAND_SAVE
+        .try_with(|saved| *saved.borrow_mut() = Some(config.clone()))
+        .is_ok()
+}
+
+// Runs a command so everything it saves becomes one undo step, recorded once it has finished
+// and no config lock is held any more
+pub async fn record_command<F: Future>(state: &State<'_, AppState>, command: F) -> F::Output {
+    // Commands run by another one, e.g. from a plugin, belong to the outer command's step
+    if COMMAND_SAVE.try_with(|_| ()).is_ok() {
+        return command.await;
+    }
+    let before = state.config.lock().unwrap().clone();
+    let (output, saved) = COMMAND_SAVE
+        .scope(RefCell::new(None), async {
+            let output = command.await;
+            (output, COMMAND_SAVE.with(|saved| saved.borrow_mut().take()))
+        })
+        .await;
+
+    if let Some(after) = saved {
+        state.undo.lock().unwrap().record(before, after);
+    }
+    output
+}
+
+// Puts `target` back in place and re-applies every key that differs from `from`
+pub async fn restore(app: tauri::AppHandle, state: State<'_, AppState>, target: BoxtsConfig, from: &BoxtsConfig) -> Result<(), String> {
+    let changed = settings::changed_keys(from, &target);
+
+    let result = apply_restored(app, state, target, &changed).await;
+    // Undoing and redoing move through the history, they must not become new entries
+    let _ = COMMAND_SAVE.try_with(|saved| saved.borrow_mut().take());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_volume(volume: f32) -> BoxtsConfig {
+        let mut config = BoxtsConfig::default();
+        config.tts.volume = volume;
+        config
+    }
+
+    fn volume_of(change: &Change) -> (f32, f32) {
+        (change.before.tts.volume, change.after.tts.volume)
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_stack() {
+        let mut history = UndoHistory::new();
+        history.record(with_volume(0.25), with_volume(0.5));
+        history.record(with_volume(0.5), with_volume(0.75));
+
+        assert_eq!(volume_of(&history.take_undo().unwrap()), (0.5, 0.75));
+        assert_eq!(volume_of(&history.take_undo().unwrap()), (0.25, 0.5));
+        assert!(history.take_undo().is_none());
+
+        assert_eq!(volume_of(&history.take_redo().unwrap()), (0.25, 0.5));
+        assert_eq!(volume_of(&history.take_redo().unwrap()), (0.5, 0.75));
+        assert!(history.take_redo().is_none());
+    }
+
+    #[test]
+    fn commands_that_change_nothing_are_not_recorded() {
+        let mut history = UndoHistory::new();
+        history.record(with_volume(0.5), with_volume(0.5));
+        assert!(history.take_undo().is_none());
+    }
+
+    #[test]
+    fn a_new_change_clears_redo() {
+        let mut history = UndoHistory::new();
+        history.record(with_volume(0.25), with_volume(0.5));
+        history.take_undo();
+        history.record(with_volume(0.25), with_volume(0.75));
+
+        assert!(history.take_redo().is_none());
+        assert_eq!(volume_of(&history.take_undo().unwrap()), (0.25, 0.75));
+    }
+
+    #[test]
+    fn keeps_only_the_latest_changes() {
+        let mut history = UndoHistory::new();
+        for step in 0..UNDO_LIMIT + 5 {
+            history.record(with_volume(step as f32), with_volume(step as f32 + 1.0));
+        }
+
+        let mut undone = Vec::new();
+        while let Some(change) = history.take_undo() {
+            undone.push(change);
+        }
+        assert_eq!(undone.len(), UNDO_LIMIT);
+        assert_eq!(volume_of(undone.last().unwrap()), (5.0, 6.0));
+    }
+
+    #[test]
+    fn describes_one_or_several_changes() {
+        assert_eq!(describe(&with_volume(0.25), &with_volume(0.5)), "tts.volume 0.25 → 0.5");
+
+        let mut after = with_volume(0.5);
+        after.tts.voice = "amy.json".to_string();
+        assert_eq!(describe(&with_volume(0.25), &after), "2 settings (tts.voice, tts.volume)");
+    }
+
+    #[test]
+    fn changed_keys_cover_added_entries_and_lists() {
+        let before = BoxtsConfig::default();
+        let mut after = BoxtsConfig::default();
+        after.aliases.insert("quiet".to_string(), "/volume 0.2".to_string());
+        after.tts.output_devices.clear();
+
+        let changed = settings::changed_keys(&before, &after);
+        let keys: Vec<&str> = changed.iter().map(|(key, _, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["aliases.quiet", "tts.output_devices"]);
+        assert_eq!(changed[0], ("aliases.quiet".to_string(), "unset".to_string(), "/volume 0.2".to_string()));
+        assert!(settings::changed_keys(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn undoing_an_output_device_change_reapplies_the_device_list() {
+        let mut after = BoxtsConfig::default();
+        after.tts.output_devices = vec![
+            config::OutputDeviceConfig { name: "Headset".to_string(), volume: 0.5 },
+            config::OutputDeviceConfig { name: "CABLE Input".to_string(), volume: 1.0 },
+        ];
+        let mut history = UndoHistory::new();
+        history.record(BoxtsConfig::default(), after);
+
+        // restore() puts `before` back and applies every key that differs from `after`
+        let change = history.take_undo().unwrap();
+        assert_eq!(
+            settings::changed_keys(&change.after, &change.before),
+            vec![(
+                "tts.output_devices".to_string(),
+                "name: Headset, volume: 0.5 | name: CABLE Input, volume: 1".to_string(),
+                "name: Default, volume: 1".to_string(),
+            )]
+        );
+    }
+}
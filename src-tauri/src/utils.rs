@@ -1,63 +1,80 @@
 use tauri::{Manager, PhysicalPosition, Position, State};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
 use crate::AppState;
+use crate::layout::{self, Rect, Size};
 
-pub async fn move_window_center(app: tauri::AppHandle) -> Result<(), String> {
-    let window = app.get_webview_window("main")
-        .ok_or("Failed to get main window")?;
-    
-    window.center()
-        .map_err(|e| format!("Failed to center window: {}", e))?;
-    
-    Ok(())
+const DRAG_SETTLE: Duration = Duration::from_millis(400);
+// An Alt+click that never moves the window sends no Moved event to end the drag
+const DRAG_START_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn get_voices_path() -> PathBuf {
+    if cfg!(debug_assertions) {
+        PathBuf::from("../realtimetts-resources/voices")
+    } else {
+        PathBuf::from("./realtimetts-resources/voices")
+    }
 }
 
-pub async fn move_window_topleft(app: tauri::AppHandle) -> Result<(), String> {
-    let window = app.get_webview_window("main")
-        .ok_or("Failed to get main window")?;
-    
-    let current_monitor = window.current_monitor()
-        .map_err(|e| format!("Failed to get current monitor: {}", e))?
-        .ok_or("No current monitor detected")?;
-    
-    let work_area = current_monitor.work_area();
-    let margin = 10;
-    
-    let new_pos = PhysicalPosition::new(
-        work_area.position.x + margin,
-        work_area.position.y + margin
-    );
-    
-    window.set_position(Position::Physical(new_pos))
-        .map_err(|e| format!("Failed to set window position: {}", e))?;
-    
-    Ok(())
+pub fn get_models_path() -> PathBuf {
+    if cfg!(debug_assertions) {
+        PathBuf::from("../realtimetts-resources/models")
+    } else {
+        PathBuf::from("./realtimetts-resources/models")
+    }
 }
 
-pub async fn move_window_topright(app: tauri::AppHandle) -> Result<(), String> {
-    let window = app.get_webview_window("main")
-        .ok_or("Failed to get main window")?;
-    
-    let current_monitor = window.current_monitor()
-        .map_err(|e| format!("Failed to get current monitor: {}", e))?
-        .ok_or("No current monitor detected")?;
-    
-    let work_area = current_monitor.work_area();
-    let window_size = window.outer_size()
+pub fn list_voices() -> Vec<String> {
+    match std::fs::read_dir(get_voices_path()) {
+        Ok(entries) => {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                        path.file_name().and_then(|s| s.to_str()).map(|s| s.to_string())
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        }
+        Err(_) => Vec::new()
+    }
+}
+
+pub fn list_output_devices() -> Vec<String> {
+    crate::audio::output_devices()
+        .into_iter()
+        .map(|device| device.name)
+        .collect()
+}
+
+fn work_area_rect(monitor: &tauri::Monitor) -> Rect {
+    let work_area = monitor.work_area();
+    Rect {
+        x: work_area.position.x,
+        y: work_area.position.y,
+        width: work_area.size.width,
+        height: work_area.size.height,
+    }
+}
+
+fn window_size(window: &tauri::WebviewWindow) -> Result<Size, String> {
+    let size = window.outer_size()
         .map_err(|e| format!("Failed to get window size: {}", e))?;
-    let margin = 10;
-    
-    let new_pos = PhysicalPosition::new(
-        work_area.position.x + work_area.size.width as i32 - window_size.width as i32 + margin,
-        work_area.position.y + margin
-    );
-    
-    window.set_position(Position::Physical(new_pos))
-        .map_err(|e| format!("Failed to set window position: {}", e))?;
-    
-    Ok(())
+    Ok(Size { width: size.width, height: size.height })
 }
 
-pub async fn move_window_bottomleft(app: tauri::AppHandle) -> Result<(), String> {
+fn anchor_for(name: &str) -> Result<layout::Anchor, String> {
+    layout::parse_anchor(name).ok_or_else(|| format!("Unknown window position: {}", name))
+}
+
+pub async fn move_window(app: tauri::AppHandle, anchor: &str, offset_x: &str, offset_y: &str) -> Result<(), String> {
+    let anchor = anchor_for(anchor)?;
+    let offset = (layout::parse_offset(offset_x)?, layout::parse_offset(offset_y)?);
+
     let window = app.get_webview_window("main")
         .ok_or("Failed to get main window")?;
     
@@ -65,131 +82,116 @@ pub async fn move_window_bottomleft(app: tauri::AppHandle) -> Result<(), String>
         .map_err(|e| format!("Failed to get current monitor: {}", e))?
         .ok_or("No current monitor detected")?;
     
-    let work_area = current_monitor.work_area();
-    let window_size = window.outer_size()
-        .map_err(|e| format!("Failed to get window size: {}", e))?;
-    let margin = 10;
-    
-    let new_pos = PhysicalPosition::new(
-        work_area.position.x + margin,
-        work_area.position.y + work_area.size.height as i32 - window_size.height as i32 + margin
+    let (x, y) = layout::window_position(
+        work_area_rect(&current_monitor),
+        window_size(&window)?,
+        anchor,
+        offset,
+        current_monitor.scale_factor(),
     );
     
-    window.set_position(Position::Physical(new_pos))
+    window.set_position(Position::Physical(PhysicalPosition::new(x, y)))
         .map_err(|e| format!("Failed to set window position: {}", e))?;
     
     Ok(())
 }
 
-pub async fn move_window_bottomright(app: tauri::AppHandle) -> Result<(), String> {
+// Logical pixel offsets that reproduce where the window is now, relative to `anchor` on its current monitor
+pub fn current_offset(app: &tauri::AppHandle, anchor: &str) -> Result<(i32, i32, tauri::Monitor), String> {
+    let anchor = anchor_for(anchor)?;
+
     let window = app.get_webview_window("main")
         .ok_or("Failed to get main window")?;
-    
     let current_monitor = window.current_monitor()
         .map_err(|e| format!("Failed to get current monitor: {}", e))?
         .ok_or("No current monitor detected")?;
-    
-    let work_area = current_monitor.work_area();
-    let window_size = window.outer_size()
-        .map_err(|e| format!("Failed to get window size: {}", e))?;
-    let margin = 10;
-    
-    let new_pos = PhysicalPosition::new(
-        work_area.position.x + work_area.size.width as i32 - window_size.width as i32 + margin,
-        work_area.position.y + work_area.size.height as i32 - window_size.height as i32 + margin
+    let position = window.outer_position()
+        .map_err(|e| format!("Failed to get window position: {}", e))?;
+
+    let (offset_x, offset_y) = layout::offset_for_position(
+        work_area_rect(&current_monitor),
+        window_size(&window)?,
+        anchor,
+        (position.x, position.y),
+        current_monitor.scale_factor(),
     );
-    
-    window.set_position(Position::Physical(new_pos))
-        .map_err(|e| format!("Failed to set window position: {}", e))?;
-    
-    Ok(())
+    Ok((offset_x, offset_y, current_monitor))
 }
 
-pub async fn switch_to_next_monitor(app: tauri::AppHandle) -> Result<(String, u32), String> {
-    let window = app.get_webview_window("main")
-        .ok_or("Failed to get main window")?;
-    
-    let current_monitor = window.current_monitor()
-        .map_err(|e| format!("Failed to get current monitor: {}", e))?
-        .ok_or("No current monitor detected")?;
-    
-    let all_monitors = app.available_monitors()
-        .map_err(|e| format!("Failed to get available monitors: {}", e))?;
-    
-    if all_monitors.len() <= 1 {
-        return Ok(("Only one monitor available".to_string(), 0));
-    }
-    
-    let current_idx = all_monitors.iter().position(|m| 
-        m.name() == current_monitor.name()
-    ).ok_or("Current monitor not found in available monitors")?;
-    
-    let next_idx = (current_idx + 1) % all_monitors.len();
-    let next_monitor_id = next_idx as u32;
-    
-    // Use switch_to_monitor to handle the actual switching
-    let (switch_result, new_monitor_id) = switch_to_monitor(app, next_monitor_id).await?;
-    
-    Ok((switch_result, new_monitor_id))
+// Set while the user drags the overlay, so moves made by boxts itself aren't saved
+#[derive(Default)]
+pub struct WindowDrag {
+    active: AtomicBool,
+    moves: AtomicU64,
 }
 
+// The webview rarely sees the mouse-up once the OS has taken over the drag, so a drag without any
+// movement simply times out; one that moves ends when the window settles, see window_moved
+pub fn begin_window_drag(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    state.window_drag.active.store(true, Ordering::SeqCst);
+    let generation = state.window_drag.moves.fetch_add(1, Ordering::SeqCst) + 1;
 
-pub async fn switch_to_monitor(app: tauri::AppHandle, monitor_id: u32) -> Result<(String, u32), String> {
-    let all_monitors = app.available_monitors()
-        .map_err(|e| format!("Failed to get available monitors: {}", e))?;
-    
-    let target_monitor = if let Some(monitor) = all_monitors.get(monitor_id as usize) {
-        monitor.clone()
-    } else {
-        all_monitors.get(0)
-            .ok_or("No monitors available")?
-            .clone()
-    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(DRAG_START_TIMEOUT).await;
+        let state = app.state::<AppState>();
+        if state.window_drag.moves.load(Ordering::SeqCst) == generation {
+            state.window_drag.active.store(false, Ordering::SeqCst);
+        }
+    });
+}
 
-    let target_monitor_idx = all_monitors.iter().position(|m| 
-        m.name() == target_monitor.name()
-    ).unwrap();
+// Saves the dragged position once the window has stopped moving for a moment
+pub fn window_moved(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    if !state.window_drag.active.load(Ordering::SeqCst) {
+        return;
+    }
+    let generation = state.window_drag.moves.fetch_add(1, Ordering::SeqCst) + 1;
 
-    let target_monitor_id = target_monitor_idx as u32;
-    
-    let window = app.get_webview_window("main")
-        .ok_or("Failed to get main window")?;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(DRAG_SETTLE).await;
+        let state = app.state::<AppState>();
+        if state.window_drag.moves.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        state.window_drag.active.store(false, Ordering::SeqCst);
 
-    let work_area = target_monitor.work_area();
-    let center_pos = PhysicalPosition::new(
-        work_area.position.x + work_area.size.width as i32 / 2,
-        work_area.position.y + work_area.size.height as i32 / 2
-    );
-    
-    window.set_position(Position::Physical(center_pos))
-        .map_err(|e| format!("Failed to move to target monitor: {}", e))?;
-    
-    Ok((format!("Switched to monitor: {}", 
-        target_monitor.name().map_or("Unknown", |v| v)), target_monitor_id))
+        if let Err(e) = save_dragged_position(&app, &state) {
+            crate::log::tauri_log(&format!("Failed to save window position: {}", e));
+        }
+    });
 }
 
-pub async fn apply_window_position(app: tauri::AppHandle, _state: State<'_, AppState>, position: &str) -> Result<String, String> {
+fn save_dragged_position(app: &tauri::AppHandle, state: &State<AppState>) -> Result<(), String> {
+    let anchor = crate::config::get_window_position(state);
+    let (offset_x, offset_y, monitor) = current_offset(app, &anchor)?;
+    let monitor_id = crate::monitors::index_of(app, &monitor);
+
+    let mut config = state.config.lock().unwrap();
+    config.window.offset_x = format!("{}px", offset_x);
+    config.window.offset_y = format!("{}px", offset_y);
+    if let Some(monitor_id) = monitor_id {
+        config.window.monitor_id = monitor_id as u32;
+    }
+    config.window.monitor = Some(crate::monitors::identity(&monitor));
+    crate::config::save_config(state, &config).map_err(|e| format!("Failed to save config: {}", e))?;
+    crate::log::tauri_log(&format!("Window position saved: {} {}px {}px", anchor, offset_x, offset_y));
+    Ok(())
+}
+
+pub async fn apply_window_position(app: tauri::AppHandle, state: State<'_, AppState>, position: &str) -> Result<String, String> {
+    let (offset_x, offset_y) = crate::config::get_window_offset(&state);
+    move_window(app, position, &offset_x, &offset_y).await?;
+
     match position {
-        "center" => {
-            move_window_center(app).await?;
-            Ok("Window centered".to_string())
-        },
-        "topleft" => {
-            move_window_topleft(app).await?;
-            Ok("Window moved to top-left".to_string())
-        },
-        "topright" => {
-            move_window_topright(app).await?;
-            Ok("Window moved to top-right".to_string())
-        },
-        "bottomleft" => {
-            move_window_bottomleft(app).await?;
-            Ok("Window moved to bottom-left".to_string())
-        },
-        "bottomright" => {
-            move_window_bottomright(app).await?;
-            Ok("Window moved to bottom-right".to_string())
-        },
-        _ => Err(format!("Unknown window position: {}", position))
+        "center" => Ok("Window centered".to_string()),
+        "topleft" => Ok("Window moved to top-left".to_string()),
+        "topright" => Ok("Window moved to top-right".to_string()),
+        "bottomleft" => Ok("Window moved to bottom-left".to_string()),
+        "bottomright" => Ok("Window moved to bottom-right".to_string()),
+        _ => Ok(format!("Window moved to {}", position)),
     }
 }
\ No newline at end of file
@@ -0,0 +1,130 @@
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+// Quiet period before a queued snapshot is written, so bursts of changes become one write
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+enum WriteRequest<T> {
+    Save(T),
+    Discard(Box<dyn FnOnce() + Send>),
+    Flush(oneshot::Sender<()>),
+}
+
+// Writes snapshots on a background task, at most once per burst, so callers never hold
+// their mutex across file I/O. Only the latest snapshot of a burst reaches the disk.
+pub struct DebouncedWriter<T> {
+    name: &'static str,
+    sender: mpsc::UnboundedSender<WriteRequest<T>>,
+}
+
+impl<T> Clone for DebouncedWriter<T> {
+    fn clone(&self) -> Self {
+        Self { name: self.name, sender: self.sender.clone() }
+    }
+}
+
+impl<T: Send + 'static> DebouncedWriter<T> {
+    // `name` is what failures are logged as, e.g. "Failed to save config: ..."
+    pub fn spawn(name: &'static str, write: fn(T) -> Result<(), String>) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+
+        tauri::async_runtime::spawn(async move {
+            let mut pending: Option<T> = None;
+            loop {
+                let request = if pending.is_some() {
+                    match tokio::time::timeout(SAVE_DEBOUNCE, receiver.recv()).await {
+                        Ok(request) => request,
+                        Err(_) => {
+                            write_pending(name, write, &mut pending).await;
+                            continue;
+                        }
+                    }
+                } else {
+                    receiver.recv().await
+                };
+
+                match request {
+                    Some(WriteRequest::Save(snapshot)) => pending = Some(snapshot),
+                    Some(WriteRequest::Discard(then)) => {
+                        // Whatever was still queued belonged to what is being discarded
+                        pending = None;
+                        then();
+                    },
+                    Some(WriteRequest::Flush(done)) => {
+                        write_pending(name, write, &mut pending).await;
+                        let _ = done.send(());
+                    },
+                    None => {
+                        write_pending(name, write, &mut pending).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self { name, sender }
+    }
+
+    pub fn save(&self, snapshot: T) -> Result<(), String> {
+        self.sender
+            .send(WriteRequest::Save(snapshot))
+            .map_err(|_| format!("The {} writer is not running", self.name))
+    }
+
+    // Drops the queued snapshot, then runs `then` in order with the writes, e.g. to remove the file
+    pub fn discard(&self, then: impl FnOnce() + Send + 'static) -> Result<(), String> {
+        self.sender
+            .send(WriteRequest::Discard(Box::new(then)))
+            .map_err(|_| format!("The {} writer is not running", self.name))
+    }
+
+    // Waits until every queued snapshot is on disk
+    pub async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(WriteRequest::Flush(done)).is_ok() {
+            let _ = wait.await;
+        }
+    }
+}
+
+async fn write_pending<T: Send + 'static>(name: &str, write: fn(T) -> Result<(), String>, pending: &mut Option<T>) {
+    if let Some(snapshot) = pending.take() {
+        match tauri::async_runtime::spawn_blocking(move || write(snapshot)).await {
+            Ok(Ok(())) => {},
+            Ok(Err(e)) => crate::log::tauri_log(&format!("Failed to save {}: {}", name, e)),
+            Err(e) => crate::log::tauri_log(&format!("The {} save task failed: {}", name, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    static WRITTEN: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+    fn record_write(value: u32) -> Result<(), String> {
+        WRITTEN.lock().unwrap().push(value);
+        Ok(())
+    }
+
+    #[test]
+    fn writes_only_the_latest_snapshot_and_honours_discard() {
+        let writer = DebouncedWriter::spawn("test", record_write);
+        tauri::async_runtime::block_on(async {
+            for value in 1..=3 {
+                writer.save(value).unwrap();
+            }
+            writer.flush().await;
+            assert_eq!(*WRITTEN.lock().unwrap(), vec![3]);
+
+            writer.save(4).unwrap();
+            let (discarded, wait) = oneshot::channel();
+            writer.discard(move || { let _ = discarded.send(()); }).unwrap();
+            wait.await.unwrap();
+            writer.flush().await;
+            assert_eq!(*WRITTEN.lock().unwrap(), vec![3]);
+        });
+    }
+}
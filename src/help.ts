@@ -0,0 +1,127 @@
+import { invoke } from "@tauri-apps/api/core";
+
+interface CommandInfo {
+  name: string;
+  aliases: string[];
+  description: string;
+  category: string;
+  usage: string;
+  examples: string[];
+  current_value: string | null;
+  has_items: boolean;
+  waits_for_ready: boolean;
+}
+
+interface HotkeyInfo {
+  keys: string;
+  description: string;
+}
+
+const createMeta = (label: string, value: string, className = "") => {
+  const meta = document.createElement("div");
+  meta.className = "command-meta";
+  meta.textContent = `${label}: `;
+
+  const span = document.createElement("span");
+  span.className = className;
+  span.textContent = value;
+  meta.appendChild(span);
+  return meta;
+};
+
+const createCommand = (command: CommandInfo): HTMLElement => {
+  const element = document.createElement("div");
+  element.className = "command";
+
+  const name = document.createElement("div");
+  name.className = "command-name";
+  name.textContent = command.usage;
+  element.appendChild(name);
+
+  const description = document.createElement("div");
+  description.className = "command-desc";
+  description.textContent = command.description;
+  element.appendChild(description);
+
+  if (command.aliases.length > 0) {
+    const aliases = command.aliases.map((alias) => `/${alias}`).join(", ");
+    element.appendChild(createMeta("Aliases", aliases));
+  }
+  if (command.current_value !== null) {
+    element.appendChild(
+      createMeta("Current", command.current_value, "current")
+    );
+  }
+  if (command.examples.length > 0) {
+    element.appendChild(createMeta("Examples", command.examples.join("  ")));
+  }
+
+  return element;
+};
+
+const createSection = (title: string, cards: HTMLElement[]) => {
+  const section = document.createElement("div");
+  section.className = "section";
+
+  const heading = document.createElement("h3");
+  heading.textContent = title;
+  section.appendChild(heading);
+
+  const grid = document.createElement("div");
+  grid.className = "command-grid";
+  cards.forEach((card) => grid.appendChild(card));
+  section.appendChild(grid);
+
+  return section;
+};
+
+const renderCommands = (commands: CommandInfo[]) => {
+  const container = document.getElementById("commands");
+  if (!container) {
+    return;
+  }
+
+  // Categories keep the order the registry declares them in
+  const categories = new Map<string, CommandInfo[]>();
+  for (const command of commands) {
+    const group = categories.get(command.category) ?? [];
+    group.push(command);
+    categories.set(command.category, group);
+  }
+
+  for (const [category, group] of categories) {
+    container.appendChild(createSection(category, group.map(createCommand)));
+  }
+};
+
+const renderHotkeys = (hotkeys: HotkeyInfo[]) => {
+  const container = document.getElementById("hotkeys");
+  if (!container) {
+    return;
+  }
+
+  const cards = hotkeys.map((hotkey) => {
+    const element = document.createElement("div");
+    element.className = "command";
+
+    const keys = document.createElement("div");
+    keys.className = "command-name";
+    keys.textContent = hotkey.keys;
+    element.appendChild(keys);
+
+    const description = document.createElement("div");
+    description.className = "command-desc";
+    description.textContent = hotkey.description;
+    element.appendChild(description);
+    return element;
+  });
+  container.appendChild(createSection("Hotkeys", cards));
+};
+
+invoke<CommandInfo[]>("get_command_info")
+  .then(renderCommands)
+  .catch((error) => console.error("Error getting command info:", error));
+
+invoke<HotkeyInfo[]>("get_hotkeys")
+  .then(renderHotkeys)
+  .catch((error) => console.error("Error getting hotkeys:", error));
@@ -3,35 +3,131 @@ import ReactDOM from "react-dom/client";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import {
-  register,
-  unregister,
-  isRegistered,
-} from "@tauri-apps/plugin-global-shortcut";
 import { handleItemCommand } from "./itemSelector";
 
-function App() {
+const FONT_FAMILY = "Consolas, 'Courier New', monospace";
+
+interface CommandInfo {
+  name: string;
+  aliases: string[];
+  description: string;
+  category: string;
+  usage: string;
+  has_items: boolean;
+  multi_select: boolean;
+  waits_for_ready: boolean;
+}
+
+// Candidates replace text[start, end), offsets as used by the input element
+interface CompletionResult {
+  start: number;
+  end: number;
+  candidates: string[];
+}
+
+// The [theme] config section, see theme.rs
+interface Theme {
+  preset: string;
+  background: string;
+  text: string;
+  border: string;
+  suggestion: string;
+  error: string;
+  item_primary: string;
+  item_secondary: string;
+  opacity: number;
+  width: number;
+  height: number;
+  expanded_height: number;
+  font_scale: number;
+}
+
+// The dark preset from theme.rs, for when the theme can't be loaded
+const DEFAULT_THEME: Theme = {
+  preset: "dark",
+  background: "#131313",
+  text: "#eeeeee",
+  border: "#535353",
+  suggestion: "#79f079",
+  error: "#ff6b6b",
+  item_primary: "#00ffea",
+  item_secondary: "#fffb00",
+  opacity: 0.5,
+  width: 444,
+  height: 54,
+  expanded_height: 220,
+  font_scale: 1.0,
+};
+
+// Sizes of the overlay's parts, scaled from a 444×54 window with 16px text
+const themeMetrics = (theme: Theme) => {
+  const scale = theme.font_scale;
+  const lineHeight = Math.round(19 * scale);
+  const inputHeight = theme.height - 19;
+  return {
+    inputWidth: theme.width - 44,
+    inputHeight,
+    fontSize: Math.round(16 * scale),
+    lineHeight,
+    smallFontSize: Math.round(10 * scale),
+    textTop: Math.round((inputHeight - lineHeight) / 2),
+    notificationTop: inputHeight - Math.round(10 * scale),
+    hintTop: inputHeight - Math.round(13 * scale),
+    expandedInputHeight: theme.expanded_height - 19,
+    // Appended to the background color, e.g. 0.5 -> "80"
+    backgroundAlpha: Math.round(theme.opacity * 255)
+      .toString(16)
+      .padStart(2, "0"),
+  };
+};
+
+function App({ initialTheme }: { initialTheme: Theme }) {
   const [text, setText] = useState("");
   const [cursorPos, setCursorPos] = useState(0);
   const [hasSelection, setHasSelection] = useState(false);
   const [availableCommands, setAvailableCommands] = useState<string[]>([]);
+  const [commandInfo, setCommandInfo] = useState<Record<string, CommandInfo>>(
+    {}
+  );
   const [suggestion, setSuggestion] = useState("");
   const [showErrorCursor, setShowErrorCursor] = useState(false);
   const [showSuggestionCursor, setShowSuggestionCursor] = useState(false);
   const [animateCursor, setAnimateCursor] = useState(false);
   const inputRef = useRef<HTMLInputElement>(null);
 
+  // Multi-line editor; Rust resizes the window and sends "expanded-changed"
+  const [expanded, setExpanded] = useState(false);
+
   // Item selector state
   const [items, setItems] = useState<string[]>([]);
   const [selectedItemIndex, setSelectedItemIndex] = useState(0);
   const [commandForItems, setCommandForItems] = useState("");
+  // Ticked items when several can be picked; null for single-select commands
+  const [checkedItems, setCheckedItems] = useState<string[] | null>(null);
 
   // Tab cycling state
   const [tabCycleMode, setTabCycleMode] = useState(false);
-  const [, setOriginalPartial] = useState("");
+  const [cycleBase, setCycleBase] = useState<{
+    text: string;
+    start: number;
+    end: number;
+  } | null>(null);
   const [matchingCommands, setMatchingCommands] = useState<string[]>([]);
   const [currentMatchIndex, setCurrentMatchIndex] = useState(0);
 
+  // History recall: -1 means the draft is being edited rather than an entry
+  const [historyIndex, setHistoryIndex] = useState(-1);
+  const [historyDraft, setHistoryDraft] = useState("");
+  // Ctrl+R search: the typed query, how many newer matches to skip, and the match
+  const [historySearch, setHistorySearch] = useState<{
+    query: string;
+    skip: number;
+    match: string | null;
+  } | null>(null);
+
+  // Latest completion for the word under the cursor, from the Rust side
+  const [completion, setCompletion] = useState<CompletionResult | null>(null);
+
   // Loading state
   const [loading, setLoading] = useState(false);
   const [loadingDots, setLoadingDots] = useState(1);
@@ -39,75 +135,168 @@ function App() {
   // Notification state
   const [notification, setNotification] = useState("");
 
-  // Color scheme
-  const colors = {
-    background: "#131313",
-    text: "#eeeeee",
-    border: "#535353",
-    suggestion: "#79f079",
-    error: "#FF6B6B",
-    itemPrimary: "#00ffea", // Cyan for first item
-    itemSecondary: "#fffb00", // Yellow for other items
-  };
+  // Colors and sizes; Rust resizes the window and sends "theme-changed"
+  const [colors, setColors] = useState<Theme>(initialTheme);
+  const metrics = themeMetrics(colors);
 
-  const updateSuggestion = (inputText: string) => {
-    if (inputText.startsWith("/") && inputText.length > 1) {
-      const command = inputText.slice(1);
-      const match = availableCommands.find((cmd) => cmd.startsWith(command));
-      setSuggestion(match ? match.slice(command.length) : "");
-    } else {
+  const updateSuggestion = (
+    inputText: string,
+    cursor: number = inputText.length
+  ) => {
+    if (!inputText.startsWith("/") || inputText.length <= 1) {
+      setCompletion(null);
       setSuggestion("");
+      return;
     }
+
+    invoke<CompletionResult>("complete_input", { text: inputText, cursor })
+      .then((result) => {
+        setCompletion(result);
+        // Inline suggestion only when the best candidate extends what is typed
+        const typed = inputText.slice(result.start, cursor);
+        const best = result.candidates[0];
+        setSuggestion(
+          cursor === inputText.length && best && best.startsWith(typed)
+            ? best.slice(typed.length)
+            : ""
+        );
+      })
+      .catch(console.error);
   };
 
   const isTextError = () => {
     if (text.startsWith("/") && text.length > 1) {
-      const hasMultipleSlashes = text.slice(1).includes("/");
-      if (hasMultipleSlashes) {
+      // Arguments and chained steps may contain slashes, the command name may not
+      const command = text.slice(1).split(/[ ;]/)[0];
+      if (command.includes("/")) {
         return true;
       }
 
-      const command = text.slice(1).split(" ")[0];
       const isValidCommand = availableCommands.includes(command);
       return !suggestion && !isValidCommand;
     }
     return false;
   };
 
+  // Only a half-typed command name blocks submission, arguments may be free text
   const isIncompleteCommand = () => {
+    const completingCommand =
+      completion !== null && text.slice(0, completion.start).endsWith("/");
     return (
       text.startsWith("/") &&
       (text.length === 1 ||
-        (text.length > 1 && suggestion && suggestion.length > 0))
+        (text.length > 1 &&
+          completingCommand &&
+          suggestion &&
+          suggestion.length > 0))
     );
   };
 
-  const findMatchingCommands = (partial: string) => {
-    return availableCommands.filter((cmd) => cmd.startsWith(partial));
+  const applyCompletion = (
+    base: { text: string; start: number; end: number },
+    candidate: string
+  ) => {
+    const fullText =
+      base.text.slice(0, base.start) + candidate + base.text.slice(base.end);
+    setText(fullText);
+    setSuggestion("");
+
+    const newPos = base.start + candidate.length;
+    setTimeout(() => {
+      if (inputRef.current) {
+        inputRef.current.setSelectionRange(newPos, newPos);
+        setCursorPos(newPos);
+      }
+    }, 0);
+    updateSuggestion(fullText, newPos);
   };
 
   const resetTabCycleMode = () => {
     setTabCycleMode(false);
-    setOriginalPartial("");
+    setCycleBase(null);
     setMatchingCommands([]);
     setCurrentMatchIndex(0);
   };
 
+  const showText = (newText: string) => {
+    setText(newText);
+    setSuggestion("");
+    updateSuggestion(newText);
+    setTimeout(() => {
+      if (inputRef.current) {
+        inputRef.current.setSelectionRange(newText.length, newText.length);
+        setCursorPos(newText.length);
+      }
+    }, 0);
+  };
+
+  const recallHistory = async (step: number) => {
+    const draft = historyIndex === -1 ? text : historyDraft;
+    const nextIndex = historyIndex + step;
+    if (nextIndex < 0) {
+      if (historyIndex !== -1) {
+        setHistoryIndex(-1);
+        showText(draft);
+      }
+      return;
+    }
+
+    // The draft decides what is browsed: commands after "/", spoken lines otherwise
+    const kind = draft.startsWith("/") ? "command" : "text";
+    const entry = await invoke<string | null>("recall_history", {
+      kind,
+      index: nextIndex,
+    });
+    if (entry === null) {
+      return;
+    }
+    if (historyIndex === -1) {
+      setHistoryDraft(text);
+    }
+    setHistoryIndex(nextIndex);
+    showText(entry);
+  };
+
+  const searchHistory = async (query: string, skip: number) => {
+    const match = await invoke<string | null>("search_history", {
+      query,
+      kind: null,
+      skip,
+    });
+    // Repeating Ctrl+R past the oldest match keeps the last one found
+    if (match === null && skip > 0 && historySearch) {
+      return;
+    }
+    setHistorySearch({ query, skip, match });
+    setNotification(
+      `search "${query}": ${match === null ? "no match" : match}`
+    );
+  };
+
+  const endHistorySearch = () => {
+    setHistorySearch(null);
+    setNotification("");
+  };
+
+  const waitsForReady = (command: string) => {
+    return commandInfo[command]?.waits_for_ready ?? false;
+  };
+
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
 
     if (items.length > 0) {
       // Set loading state immediately for commands that will trigger ready signal
-      if (
-        ["start", "clonevoice", "changevoice", "outputdevice"].includes(
-          commandForItems
-        )
-      ) {
+      if (waitsForReady(commandForItems)) {
         setLoading(true);
       }
 
-      const selectedItem = items[selectedItemIndex];
-      const command = `/${commandForItems} ${selectedItem}`;
+      // Nothing ticked in a multi-select means the highlighted item, as in a single-select
+      const ticked = checkedItems
+        ? items.filter((item) => checkedItems.includes(item))
+        : [];
+      const picks = ticked.length > 0 ? ticked : [items[selectedItemIndex]];
+      const command = `/${commandForItems} ${picks.join(" | ")}`;
 
       try {
         await invoke("process_input", { text: command });
@@ -116,6 +305,7 @@ function App() {
       }
       setItems([]);
       setCommandForItems("");
+      setCheckedItems(null);
       setText("");
       return;
     }
@@ -145,6 +335,7 @@ function App() {
         setItems,
         setSelectedItemIndex,
         setCommandForItems,
+        setCheckedItems,
         setText,
       });
       if (handled) {
@@ -152,12 +343,15 @@ function App() {
       }
     }
 
-    // Set loading state for commands that will trigger ready signal
+    // Set loading state for commands that will trigger ready signal; a chain
+    // sends one ready signal once all of its steps have finished
     if (text.startsWith("/")) {
-      const commandName = text.slice(1).split(" ")[0];
-      if (
-        ["stop", "ready", "clonevoice", "restartserver"].includes(commandName)
-      ) {
+      const commandNames = text
+        .split(";")
+        .map((step) => step.trim())
+        .filter((step) => step.startsWith("/"))
+        .map((step) => step.slice(1).split(" ")[0]);
+      if (commandNames.some(waitsForReady)) {
         setLoading(true);
       }
     }
@@ -171,6 +365,7 @@ function App() {
     setText("");
     setCursorPos(0);
     setSuggestion("");
+    setHistoryIndex(-1);
     resetTabCycleMode();
     // Reset input scroll position
     if (inputRef.current) {
@@ -178,7 +373,60 @@ function App() {
     }
   };
 
+  // The whole editor is one utterance, then the overlay goes back to a single line
+  const submitExpanded = async () => {
+    try {
+      await invoke("submit_expanded", { text });
+    } catch (error) {
+      // Keep the text and the editor open so nothing typed is lost
+      console.error("Error submitting expanded input:", error);
+      setNotification(String(error));
+      return;
+    }
+    setText("");
+    setCursorPos(0);
+    invoke("set_expanded", { expanded: false }).catch(console.error);
+  };
+
   const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (expanded) {
+      // Enter is a new line here, everything else is left to the textarea
+      if (e.ctrlKey && e.key === "Enter") {
+        e.preventDefault();
+        submitExpanded();
+      } else if (e.key === "Escape" || (e.ctrlKey && e.key === "e")) {
+        e.preventDefault();
+        invoke("set_expanded", { expanded: false }).catch(console.error);
+      }
+      return;
+    }
+
+    if (e.ctrlKey && e.key === "e" && items.length === 0) {
+      e.preventDefault();
+      endHistorySearch();
+      resetTabCycleMode();
+      invoke("set_expanded", { expanded: true }).catch(console.error);
+      return;
+    }
+
+    if (historySearch) {
+      if (e.key === "Enter" || e.key === "Escape") {
+        // Enter takes the match into the input, Escape keeps the query
+        e.preventDefault();
+        if (e.key === "Enter" && historySearch.match !== null) {
+          showText(historySearch.match);
+        }
+        endHistorySearch();
+        return;
+      } else if (e.ctrlKey && e.key === "r") {
+        e.preventDefault();
+        searchHistory(historySearch.query, historySearch.skip + 1);
+        return;
+      } else if (e.key.startsWith("Arrow") || e.key === "Tab") {
+        endHistorySearch();
+      }
+    }
+
     if (e.key === "Enter") {
       e.preventDefault();
       handleSubmit(e);
@@ -196,10 +444,19 @@ function App() {
         setSelectedItemIndex((prevIndex) =>
           prevIndex > 0 ? prevIndex - 1 : items.length - 1
         );
+      } else if (e.key === " " && checkedItems) {
+        e.preventDefault();
+        const item = items[selectedItemIndex];
+        setCheckedItems(
+          checkedItems.includes(item)
+            ? checkedItems.filter((checked) => checked !== item)
+            : [...checkedItems, item]
+        );
       } else if (e.key === "Escape") {
         e.preventDefault();
         setItems([]);
         setCommandForItems("");
+        setCheckedItems(null);
         setSelectedItemIndex(0);
       }
     } else {
@@ -210,42 +467,21 @@ function App() {
         if (text.startsWith("/") && text.length > 1) {
           if (!tabCycleMode) {
             // First tab - enter cycle mode
-            const partial = text.slice(1);
-            const matches = findMatchingCommands(partial);
+            const matches = completion?.candidates ?? [];
 
-            if (matches.length > 0) {
+            if (completion && matches.length > 0) {
+              const base = { text, start: completion.start, end: completion.end };
               setTabCycleMode(true);
-              setOriginalPartial(partial);
+              setCycleBase(base);
               setMatchingCommands(matches);
               setCurrentMatchIndex(0);
-
-              const fullCommand = `/${matches[0]}`;
-              setText(fullCommand);
-              setSuggestion("");
-
-              setTimeout(() => {
-                if (inputRef.current) {
-                  const newPos = fullCommand.length;
-                  inputRef.current.setSelectionRange(newPos, newPos);
-                  setCursorPos(newPos);
-                }
-              }, 0);
+              applyCompletion(base, matches[0]);
             }
-          } else {
+          } else if (cycleBase) {
             // Subsequent tabs - cycle through matches
             const nextIndex = (currentMatchIndex + 1) % matchingCommands.length;
             setCurrentMatchIndex(nextIndex);
-
-            const fullCommand = `/${matchingCommands[nextIndex]}`;
-            setText(fullCommand);
-
-            setTimeout(() => {
-              if (inputRef.current) {
-                const newPos = fullCommand.length;
-                inputRef.current.setSelectionRange(newPos, newPos);
-                setCursorPos(newPos);
-              }
-            }, 0);
+            applyCompletion(cycleBase, matchingCommands[nextIndex]);
           }
         } else if (suggestion) {
           // Legacy behavior for non-cycling cases
@@ -269,6 +505,21 @@ function App() {
       ) {
         resetTabCycleMode();
         updateCursorPos();
+        // Completion follows the cursor once it has moved
+        setTimeout(() => {
+          if (inputRef.current) {
+            updateSuggestion(text, inputRef.current.selectionStart ?? text.length);
+          }
+        }, 0);
+      } else if (e.key === "ArrowUp" || e.key === "ArrowDown") {
+        e.preventDefault();
+        resetTabCycleMode();
+        recallHistory(e.key === "ArrowUp" ? 1 : -1);
+      } else if (e.ctrlKey && e.key === "r") {
+        // Reverse search through everything typed, starting from the current text
+        e.preventDefault();
+        resetTabCycleMode();
+        searchHistory(text, 0);
       } else if (e.ctrlKey && e.key === "a") {
         resetTabCycleMode();
         updateCursorPos();
@@ -293,7 +544,7 @@ function App() {
   const getCanvasContext = () => {
     const canvas = document.createElement("canvas");
     const ctx = canvas.getContext("2d")!;
-    ctx.font = "16px Consolas, 'Courier New', monospace";
+    ctx.font = `${metrics.fontSize}px ${FONT_FAMILY}`;
     return ctx;
   };
 
@@ -316,8 +567,28 @@ function App() {
 
   // Fetch available commands on startup
   useEffect(() => {
-    invoke<string[]>("get_available_commands")
-      .then(setAvailableCommands)
+    const fetchAvailableCommands = () => {
+      invoke<string[]>("get_available_commands")
+        .then(setAvailableCommands)
+        .catch(console.error);
+    };
+    fetchAvailableCommands();
+
+    // Aliases are part of the command list, so refresh when they change
+    listen("commands-changed", fetchAvailableCommands);
+
+    // Index by name and alias so typed aliases resolve to the same info
+    invoke<CommandInfo[]>("get_command_info")
+      .then((commands) => {
+        const byName: Record<string, CommandInfo> = {};
+        for (const command of commands) {
+          byName[command.name] = command;
+          for (const alias of command.aliases) {
+            byName[alias] = command;
+          }
+        }
+        setCommandInfo(byName);
+      })
       .catch(console.error);
   }, []);
 
@@ -328,11 +599,38 @@ function App() {
     });
   }, []);
 
+  // Listen for /expand, Ctrl+E and Escape; collapsing joins the lines back up
+  useEffect(() => {
+    listen<boolean>("expanded-changed", (event) => {
+      setExpanded(event.payload);
+      if (!event.payload) {
+        setText((current) => current.replace(/\s*\n\s*/g, " "));
+        setSuggestion("");
+      }
+    });
+  }, []);
+
+  // Listen for /theme and /set theme.* changes
+  useEffect(() => {
+    listen<Theme>("theme-changed", (event) => {
+      setColors(event.payload);
+    });
+  }, []);
+
   // Listen for notification events
   useEffect(() => {
     listen("notification", (event) => {
       setNotification(event.payload as string);
     });
+
+    // Hotkeys that couldn't be registered at startup
+    invoke<string[]>("get_hotkey_problems")
+      .then((problems) => {
+        if (problems.length > 0) {
+          setNotification(problems.join(" • "));
+        }
+      })
+      .catch(console.error);
   }, []);
 
   // Loading animation
@@ -346,38 +644,10 @@ function App() {
     return () => clearInterval(interval);
   }, [loading]);
 
-  // Register global shortcut and window focus handling
+  // Window focus handling; the global shortcuts are registered by Rust
   useEffect(() => {
-    const shortcut = "Alt+Enter";
     const window = getCurrentWindow();
 
-    const setupGlobalShortcut = async () => {
-      try {
-        // Unregister if already registered (for hot reloads)
-        if (await isRegistered(shortcut)) {
-          await unregister(shortcut);
-        }
-
-        await register(shortcut, async (event) => {
-          if (event.state === "Pressed") {
-            const dialogActive = await invoke<boolean>("is_dialog_active");
-            if (dialogActive) {
-              return;
-            }
-            const isVisible = await window.isVisible();
-            if (isVisible) {
-              await window.hide();
-            } else {
-              await window.show();
-              await window.setFocus();
-            }
-          }
-        });
-      } catch (error) {
-        console.error("Failed to register global shortcut:", error);
-      }
-    };
-
     const setupFocusHandler = async () => {
       try {
         // Hide window when it loses focus (based on configuration)
@@ -407,7 +677,6 @@ function App() {
       }
     };
 
-    setupGlobalShortcut();
     let unlistenFocus: (() => void) | undefined;
 
     setupFocusHandler().then((unlisten) => {
@@ -415,13 +684,20 @@ function App() {
     });
 
     return () => {
-      unregister(shortcut).catch(console.error);
       if (unlistenFocus) {
         unlistenFocus();
       }
     };
   }, []);
 
+  // Alt+drag moves the overlay; Rust saves where it ends up
+  const handleMouseDown = (e: React.MouseEvent) => {
+    if (e.button === 0 && e.altKey) {
+      e.preventDefault();
+      invoke("start_window_drag").catch(console.error);
+    }
+  };
+
   // Prevent Alt key default behavior to avoid Windows Alt mode
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
@@ -434,6 +710,79 @@ function App() {
     return () => window.removeEventListener("keydown", handleKeyDown);
   }, []);
 
+  if (expanded) {
+    return (
+      <>
+        <style>
+          {`
+            textarea::selection {
+              background-color: ${colors.text};
+              color: ${colors.background};
+            }
+          `}
+        </style>
+        <form
+          onSubmit={(e) => e.preventDefault()}
+          onKeyDown={handleKeyDown}
+          onMouseDown={handleMouseDown}
+          style={{
+            background: "transparent",
+            margin: 0,
+            padding: 0,
+            position: "relative",
+          }}
+        >
+          <textarea
+            value={text}
+            onChange={(e) => {
+              setText(e.target.value);
+              setNotification("");
+            }}
+            autoFocus
+            spellCheck={false}
+            onContextMenu={(e) => e.preventDefault()}
+            style={{
+              display: "block",
+              width: `${metrics.inputWidth}px`,
+              height: `${metrics.expandedInputHeight - 16}px`,
+              borderRadius: "4px",
+              border: `1px solid ${colors.border}`,
+              padding: "8px 10px",
+              margin: 0,
+              fontSize: `${metrics.fontSize}px`,
+              fontFamily: FONT_FAMILY,
+              lineHeight: `${metrics.lineHeight}px`,
+              outline: "none",
+              resize: "none",
+              backgroundColor: `${colors.background}${metrics.backgroundAlpha}`,
+              color: colors.text,
+              caretColor: colors.text,
+            }}
+          />
+          <div
+            style={{
+              position: "absolute",
+              left: "10px",
+              top: `${metrics.expandedInputHeight + 4}px`,
+              fontSize: `${metrics.smallFontSize}px`,
+              fontFamily: FONT_FAMILY,
+              fontStyle: "italic",
+              color: colors.suggestion,
+            }}
+          >
+            {notification || "ctrl+enter • speak   esc • single line"}
+          </div>
+        </form>
+      </>
+    );
+  }
+
+  // Single-select lists start at the current value; a multi-select shows what is ticked
+  const itemTicked = checkedItems?.includes(items[selectedItemIndex]) ?? false;
+  const itemColor = (checkedItems ? itemTicked : selectedItemIndex === 0)
+    ? colors.suggestion
+    : colors.error;
+
   return (
     <>
       <style>
@@ -447,6 +796,7 @@ function App() {
       <form
         onSubmit={handleSubmit}
         onKeyDown={handleKeyDown}
+        onMouseDown={handleMouseDown}
         style={{
           background: "transparent",
           margin: 0,
@@ -461,15 +811,15 @@ function App() {
           placeholder=""
           spellCheck={false}
           style={{
-            width: "400px",
-            height: "35px",
+            width: `${metrics.inputWidth}px`,
+            height: `${metrics.inputHeight}px`,
             borderRadius: "4px",
             border: `1px solid ${colors.border}`,
             padding: "0 10px",
-            fontSize: "16px",
-            fontFamily: "Consolas, 'Courier New', monospace",
+            fontSize: `${metrics.fontSize}px`,
+            fontFamily: FONT_FAMILY,
             outline: "none",
-            backgroundColor: `${colors.background}80`,
+            backgroundColor: `${colors.background}${metrics.backgroundAlpha}`,
             color: "transparent",
             caretColor: "transparent",
             position: "relative",
@@ -486,13 +836,13 @@ function App() {
               position: "absolute",
               left: "0px",
               top: "0px",
-              width: "400px",
-              height: "35px",
+              width: `${metrics.inputWidth}px`,
+              height: `${metrics.inputHeight}px`,
               borderRadius: "4px",
               border: "none",
               padding: "0 10px",
-              fontSize: "16px",
-              fontFamily: "Consolas, 'Courier New', monospace",
+              fontSize: `${metrics.fontSize}px`,
+              fontFamily: FONT_FAMILY,
               fontStyle: "italic",
               outline: "none",
               backgroundColor: "transparent",
@@ -507,24 +857,23 @@ function App() {
             <input
               type="text"
               readOnly
-              value={`↕ ${items[selectedItemIndex]}`}
+              value={`↕ ${checkedItems ? (itemTicked ? "[x] " : "[ ] ") : ""}${items[selectedItemIndex]}`}
               autoFocus
               style={{
                 position: "absolute",
                 left: "0px",
                 top: "0px",
-                width: "400px",
-                height: "35px",
+                width: `${metrics.inputWidth}px`,
+                height: `${metrics.inputHeight}px`,
                 borderRadius: "4px",
                 border: "none",
                 padding: "0 10px",
-                fontSize: "16px",
-                fontFamily: "Consolas, 'Courier New', monospace",
+                fontSize: `${metrics.fontSize}px`,
+                fontFamily: FONT_FAMILY,
                 fontStyle: "italic",
                 outline: "none",
                 backgroundColor: "transparent",
-                color:
-                  selectedItemIndex === 0 ? colors.suggestion : colors.error,
+                color: itemColor,
                 caretColor: "transparent",
                 zIndex: 2,
               }}
@@ -533,16 +882,15 @@ function App() {
               style={{
                 position: "absolute",
                 left: "10px",
-                top: "22px",
-                fontSize: "10px",
-                fontFamily: "Consolas, 'Courier New', monospace",
+                top: `${metrics.hintTop}px`,
+                fontSize: `${metrics.smallFontSize}px`,
+                fontFamily: FONT_FAMILY,
                 fontStyle: "italic",
-                color:
-                  selectedItemIndex === 0 ? colors.suggestion : colors.error,
+                color: itemColor,
                 zIndex: 4,
               }}
             >
-              esc • cancel
+              {checkedItems ? "space • tick   esc • cancel" : "esc • cancel"}
             </div>
           </>
         ) : (
@@ -554,8 +902,16 @@ function App() {
               setText(e.target.value);
               setNotification(""); // Clear notification when text changes
               resetTabCycleMode();
+              setHistoryIndex(-1);
+              if (historySearch) {
+                // Typing while searching refines the query
+                searchHistory(e.target.value, 0);
+              }
               updateCursorPos();
-              updateSuggestion(e.target.value);
+              updateSuggestion(
+                e.target.value,
+                e.target.selectionStart ?? e.target.value.length
+              );
             }}
             onSelect={updateCursorPos}
             onClick={updateCursorPos}
@@ -567,13 +923,13 @@ function App() {
               position: "absolute",
               left: "0px",
               top: "0px",
-              width: "400px",
-              height: "35px",
+              width: `${metrics.inputWidth}px`,
+              height: `${metrics.inputHeight}px`,
               borderRadius: "4px",
               border: "none",
               padding: "0 10px",
-              fontSize: "16px",
-              fontFamily: "Consolas, 'Courier New', monospace",
+              fontSize: `${metrics.fontSize}px`,
+              fontFamily: FONT_FAMILY,
               outline: "none",
               backgroundColor: "transparent",
               color: isTextError() ? colors.error : colors.text,
@@ -587,13 +943,13 @@ function App() {
             style={{
               position: "absolute",
               left: `${10 + measureTextWidth(text, text.length)}px`,
-              top: "8px",
-              fontSize: "16px",
-              fontFamily: "Consolas, 'Courier New', monospace",
+              top: `${metrics.textTop}px`,
+              fontSize: `${metrics.fontSize}px`,
+              fontFamily: FONT_FAMILY,
               color: colors.suggestion,
               pointerEvents: "none",
               zIndex: 1,
-              lineHeight: "19px",
+              lineHeight: `${metrics.lineHeight}px`,
             }}
           >
             {suggestion}
@@ -612,9 +968,9 @@ function App() {
                 1 -
                 (animateCursor ? 1 : 0)
               }px`,
-              top: `${8 - (animateCursor ? 1 : 0)}px`,
+              top: `${metrics.textTop - (animateCursor ? 1 : 0)}px`,
               width: `${animateCursor ? getCharWidth() + 2 : getCharWidth()}px`,
-              height: `${animateCursor ? 21 : 19}px`,
+              height: `${metrics.lineHeight + (animateCursor ? 2 : 0)}px`,
               backgroundColor: showErrorCursor
                 ? colors.error
                 : showSuggestionCursor
@@ -624,9 +980,9 @@ function App() {
               display: "flex",
               alignItems: "center",
               justifyContent: "center",
-              fontSize: "16px",
-              fontFamily: "Consolas, 'Courier New', monospace",
-              lineHeight: "19px",
+              fontSize: `${metrics.fontSize}px`,
+              fontFamily: FONT_FAMILY,
+              lineHeight: `${metrics.lineHeight}px`,
               zIndex: 3,
               transition: "width 0.05s ease-out, height 0.05s ease-out",
             }}
@@ -641,9 +997,9 @@ function App() {
             style={{
               position: "absolute",
               left: "10px",
-              top: "25px",
-              fontSize: "10px",
-              fontFamily: "Consolas, 'Courier New', monospace",
+              top: `${metrics.notificationTop}px`,
+              fontSize: `${metrics.smallFontSize}px`,
+              fontFamily: FONT_FAMILY,
               fontStyle: "italic",
               color: colors.suggestion,
               zIndex: 4,
@@ -657,6 +1013,14 @@ function App() {
   );
 }
 
-ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  <App />
-);
+const renderApp = (theme: Theme) => {
+  ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
+    <App initialTheme={theme} />
+  );
+};
+
+// The theme sizes everything, so fetch it before the first render
+invoke<Theme>("get_theme").then(renderApp, (error) => {
+  console.error("Error loading theme:", error);
+  renderApp(DEFAULT_THEME);
+});
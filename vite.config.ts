@@ -29,4 +29,13 @@ export default defineConfig(async () => ({
       ignored: ["**/src-tauri/**"],
     },
   },
+  // help.html is opened in its own window by /help
+  build: {
+    rollupOptions: {
+      input: {
+        main: "index.html",
+        help: "help.html",
+      },
+    },
+  },
 }));